@@ -36,14 +36,18 @@ macro_rules! impl_ffi_dtor {
     };
 }
 
+/// Retrieves a string through a pair of size and value getters.
+///
+/// Errors are converted with `$volume.ffi_error(error)`, so that the owner of the handle can
+/// report them in context, such as a volume which was signalled to abort.
 #[macro_export]
 macro_rules! get_sized_utf8_string {
-    ($self: ident, $get_size: ident, $get_string: ident) => {{
+    ($self: ident, $get_size: ident, $get_string: ident, $volume: expr) => {{
         let mut name_size = 0_usize;
         let mut error = ptr::null_mut();
 
         if unsafe { $get_size($self.as_type_ref(), &mut name_size, &mut error) } != 1 {
-            return Err(Error::try_from(error)?);
+            return Err($volume.ffi_error(error));
         };
 
         if name_size == 0 {
@@ -61,7 +65,7 @@ macro_rules! get_sized_utf8_string {
                 )
             } != 1
             {
-                Err(Error::try_from(error)?)
+                Err($volume.ffi_error(error))
             } else {
                 // Discard nul terminator;
                 name.pop().expect("name_size was checked to be > 0");
@@ -74,12 +78,12 @@ macro_rules! get_sized_utf8_string {
 
 #[macro_export]
 macro_rules! get_sized_bytes {
-    ($self: ident, $get_size: ident, $get_string: ident) => {{
+    ($self: ident, $get_size: ident, $get_string: ident, $volume: expr) => {{
         let mut size = 0_usize;
         let mut error = ptr::null_mut();
 
         if unsafe { $get_size($self.as_type_ref(), &mut size, &mut error) } != 1 {
-            return Err(Error::try_from(error)?);
+            return Err($volume.ffi_error(error));
         };
 
         if size == 0 {
@@ -97,7 +101,7 @@ macro_rules! get_sized_bytes {
                 )
             } != 1
             {
-                Err(Error::try_from(error)?)
+                Err($volume.ffi_error(error))
             } else {
                 Ok(data)
             }
@@ -107,7 +111,7 @@ macro_rules! get_sized_bytes {
 
 #[macro_export]
 macro_rules! get_date_field {
-    ($self: ident, $getter: ident) => {{
+    ($self: ident, $getter: ident, $volume: expr) => {{
         use crate::utils::datetime_from_filetime;
         use chrono::prelude::*;
 
@@ -115,7 +119,7 @@ macro_rules! get_date_field {
         let mut error = ptr::null_mut();
 
         if unsafe { $getter($self.as_type_ref(), &mut date, &mut error) } != 1 {
-            Err(Error::try_from(error)?)
+            Err($volume.ffi_error(error))
         } else {
             let date = if date > 0 {
                 Some(datetime_from_filetime(date))
@@ -129,14 +133,101 @@ macro_rules! get_date_field {
 
 #[macro_export]
 macro_rules! get_u64_field {
-    ($self: ident, $getter: ident) => {{
+    ($self: ident, $getter: ident, $volume: expr) => {{
         let mut value = 0_u64;
         let mut error = ptr::null_mut();
 
         if unsafe { $getter($self.as_type_ref(), &mut value, &mut error) } != 1 {
-            Err(Error::try_from(error)?)
+            Err($volume.ffi_error(error))
         } else {
             Ok(value)
         }
     }};
 }
+
+#[macro_export]
+macro_rules! get_u32_field {
+    ($self: ident, $getter: ident, $volume: expr) => {{
+        let mut value = 0_u32;
+        let mut error = ptr::null_mut();
+
+        if unsafe { $getter($self.as_type_ref(), &mut value, &mut error) } != 1 {
+            Err($volume.ffi_error(error))
+        } else {
+            Ok(value)
+        }
+    }};
+}
+
+/// Like `get_sized_utf8_string!`, for getters which return 0 when the value is not available.
+#[macro_export]
+macro_rules! get_optional_sized_utf8_string {
+    ($self: ident, $get_size: ident, $get_string: ident, $volume: expr) => {{
+        let mut name_size = 0_usize;
+        let mut error = ptr::null_mut();
+
+        match unsafe { $get_size($self.as_type_ref(), &mut name_size, &mut error) } {
+            0 => Ok(None),
+            1 if name_size == 0 => Ok(Some(String::new())),
+            1 => {
+                let mut name = vec![0; name_size];
+                let mut error = ptr::null_mut();
+
+                if unsafe {
+                    $get_string(
+                        $self.as_type_ref(),
+                        name.as_mut_ptr(),
+                        name.len(),
+                        &mut error,
+                    )
+                } != 1
+                {
+                    Err($volume.ffi_error(error))
+                } else {
+                    // Discard nul terminator;
+                    name.pop().expect("name_size was checked to be > 0");
+                    let s =
+                        String::from_utf8(name).map_err(|e| Error::StringContainsInvalidUTF8(e))?;
+                    Ok(Some(s))
+                }
+            }
+            _ => Err($volume.ffi_error(error)),
+        }
+    }};
+}
+
+/// Like `get_sized_utf8_string!`, but returns the raw UTF-16 code units without the nul terminator.
+#[macro_export]
+macro_rules! get_sized_utf16_string {
+    ($self: ident, $get_size: ident, $get_string: ident, $volume: expr) => {{
+        let mut name_size = 0_usize;
+        let mut error = ptr::null_mut();
+
+        if unsafe { $get_size($self.as_type_ref(), &mut name_size, &mut error) } != 1 {
+            return Err($volume.ffi_error(error));
+        };
+
+        if name_size == 0 {
+            Ok(Vec::<u16>::new())
+        } else {
+            let mut name = vec![0_u16; name_size];
+            let mut error = ptr::null_mut();
+
+            if unsafe {
+                $get_string(
+                    $self.as_type_ref(),
+                    name.as_mut_ptr(),
+                    name.len(),
+                    &mut error,
+                )
+            } != 1
+            {
+                Err($volume.ffi_error(error))
+            } else {
+                // Discard nul terminator;
+                name.pop().expect("name_size was checked to be > 0");
+                Ok(name)
+            }
+        }
+    }};
+}
@@ -18,6 +18,8 @@ pub enum Error {
     StringContainsNul(#[cause] NulError),
     #[fail(display = "Failed to open file: {}", _0)]
     FailedToOpenFile(#[cause] io::Error),
+    #[fail(display = "Failed to seek to offset {}: {}", _0, _1)]
+    FailedToSeek(u64, #[cause] io::Error),
     #[fail(display = "An FFI error has occurred: {}", _0)]
     FFI(String),
     #[fail(display = "An unexpected error has occurred: {}", _0)]
@@ -265,6 +265,22 @@ impl Handle {
         Handle::from_io_handle(io_handle, flags)
     }
 
+    /// Opens a read-only handle to the part of `stream` which starts at `offset`,
+    /// such as a partition inside of a disk image.
+    ///
+    /// If `length` is given, the handle ends `length` bytes after `offset`, otherwise it
+    /// extends to the end of `stream`.
+    pub fn from_read_seek_at_offset(
+        stream: impl Read + Seek + 'static,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Handle, Error> {
+        let window = OffsetWindow::new(stream, offset, length)
+            .map_err(|e| Error::FailedToSeek(offset, e))?;
+
+        Handle::from_io_handle(IoHandle::read_seek(window), LibbfioAccessFlags::Read)
+    }
+
     pub fn from_io_handle(io_handle: IoHandle, flags: LibbfioAccessFlags) -> Result<Handle, Error> {
         let mut handle = ptr::null_mut();
         let mut error = ptr::null_mut();
@@ -455,9 +471,10 @@ impl Drop for Handle {
 #[cfg(test)]
 mod tests {
     use crate::handle::{Handle, LibbfioAccessFlags};
+    use crate::io_handle::OffsetWindow;
 
     use std::fs::File;
-    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
     use tempdir::TempDir;
 
@@ -524,6 +541,52 @@ mod tests {
         assert!(handle.write(b"Hello").is_err());
     }
 
+    #[test]
+    fn test_read_at_offset() {
+        let tmp_dir = tmp_src_dir();
+        let test_file = test_file(&tmp_dir, Some(FILE_CONTENT));
+        let test_file_path = tmp_dir.path().join(test_file).canonicalize().unwrap();
+
+        let file = File::open(test_file_path).unwrap();
+        let mut handle = Handle::from_read_seek_at_offset(file, 5, None).unwrap();
+        let mut buf = vec![];
+
+        handle.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"data");
+
+        assert_eq!(handle.seek(SeekFrom::Start(1)).unwrap(), 1);
+        buf.clear();
+        handle.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"ata");
+    }
+
+    #[test]
+    fn test_read_at_offset_with_length() {
+        let window = Cursor::new(FILE_CONTENT.to_vec());
+        let mut handle = Handle::from_read_seek_at_offset(window, 1, Some(4)).unwrap();
+        let mut buf = vec![];
+
+        handle.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"ome_");
+
+        assert_eq!(handle.seek(SeekFrom::End(-1)).unwrap(), 3);
+        buf.clear();
+        handle.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"_");
+    }
+
+    #[test]
+    fn test_window_seek_before_start_keeps_position() {
+        let mut window = OffsetWindow::new(Cursor::new(FILE_CONTENT), 5, None).unwrap();
+        let mut buf = [0; 1];
+
+        window.seek(SeekFrom::Start(1)).unwrap();
+        assert!(window.seek(SeekFrom::Current(-2)).is_err());
+
+        window.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"a");
+    }
+
     #[test]
     fn test_seek() {
         let tmp_dir = tmp_src_dir();
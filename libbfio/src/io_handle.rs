@@ -63,6 +63,95 @@ impl IoHandle {
     }
 }
 
+/// Exposes the part of `inner` which starts at `offset`, as if it were the whole stream.
+///
+/// Used to open a partition inside of a disk image. If `length` is given, reads stop at the
+/// end of the partition instead of running into the next one.
+pub(crate) struct OffsetWindow<T> {
+    inner: T,
+    offset: u64,
+    length: Option<u64>,
+    /// Position inside of the window, kept in sync with the position of `inner`.
+    position: u64,
+}
+
+impl<T: Read + Seek> OffsetWindow<T> {
+    pub(crate) fn new(mut inner: T, offset: u64, length: Option<u64>) -> Result<Self, io::Error> {
+        inner.seek(SeekFrom::Start(offset))?;
+
+        Ok(OffsetWindow {
+            inner,
+            offset,
+            length,
+            position: 0,
+        })
+    }
+
+    fn window_len(&mut self) -> Result<u64, io::Error> {
+        match self.length {
+            Some(length) => Ok(length),
+            None => Ok(self.inner.stream_len()?.saturating_sub(self.offset)),
+        }
+    }
+}
+
+/// Applies a signed seek offset to `base`, failing instead of wrapping around.
+fn offset_position(base: u64, delta: i64) -> Result<u64, io::Error> {
+    let position = if delta >= 0 {
+        base.checked_add(delta as u64)
+    } else {
+        base.checked_sub(delta.wrapping_neg() as u64)
+    };
+
+    position.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Seek to a position outside of the window",
+        )
+    })
+}
+
+impl<T: Read + Seek> Read for OffsetWindow<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        let buf = match self.length {
+            Some(length) if length.saturating_sub(self.position) < buf.len() as u64 => {
+                &mut buf[..length.saturating_sub(self.position) as usize]
+            }
+            _ => buf,
+        };
+
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<T: Read + Seek> Seek for OffsetWindow<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
+        // The target is validated before `inner` is moved, so a failed seek leaves both
+        // positions unchanged.
+        let position = match pos {
+            SeekFrom::Start(p) => p,
+            SeekFrom::Current(p) => offset_position(self.position, p)?,
+            SeekFrom::End(p) => offset_position(self.window_len()?, p)?,
+        };
+
+        let inner_position = self.offset.checked_add(position).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Seek position overflows")
+        })?;
+
+        self.inner.seek(SeekFrom::Start(inner_position))?;
+        self.position = position;
+
+        Ok(position)
+    }
+
+    fn stream_len(&mut self) -> Result<u64, io::Error> {
+        self.window_len()
+    }
+}
+
 pub const IO_ERR: i32 = LIBCERROR_ERROR_DOMAINS_LIBCERROR_ERROR_DOMAIN_IO as i32;
 pub const ARGUMENT_ERR: i32 = LIBCERROR_ERROR_DOMAINS_LIBCERROR_ERROR_DOMAIN_ARGUMENTS as i32;
 
@@ -1,6 +1,12 @@
 use crate::error::Error;
 use crate::ffi_error::{LibfsntfsErrorRef, LibfsntfsErrorRefMut};
 use crate::file_entry::FileEntry;
+use crate::file_reference::FileReference;
+use crate::guid::Guid;
+use crate::name::NtfsName;
+use crate::reparse_tag::ReparseTag;
+use crate::security_descriptor::SecurityDescriptor;
+use bitflags::bitflags;
 use chrono::{Date, DateTime, NaiveDateTime, Utc};
 use libfsntfs_sys::size64_t;
 use libyal_rs_common::ffi::AsTypeRef;
@@ -184,6 +190,11 @@ extern "C" {
         data_size: *mut size64_t,
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
+    pub fn libfsntfs_file_name_attribute_get_name_space(
+        attribute: AttributeRef,
+        name_space: *mut u8,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
     pub fn libfsntfs_file_name_attribute_get_parent_file_reference(
         attribute: AttributeRef,
         parent_file_reference: *mut u64,
@@ -417,36 +428,112 @@ pub enum AttributeWithInformation {
     EndOfAttributes(EndOfAttributes),
 }
 
+bitflags! {
+    /// The file attribute flags (`FILE_ATTRIBUTE_*`) stored in both $STANDARD_INFORMATION
+    /// and $FILE_NAME.
+    pub struct FileAttributeFlags: u32 {
+        const READ_ONLY = 0x0000_0001;
+        const HIDDEN = 0x0000_0002;
+        const SYSTEM = 0x0000_0004;
+        const DIRECTORY = 0x0000_0010;
+        const ARCHIVE = 0x0000_0020;
+        const DEVICE = 0x0000_0040;
+        const NORMAL = 0x0000_0080;
+        const TEMPORARY = 0x0000_0100;
+        const SPARSE = 0x0000_0200;
+        const REPARSE_POINT = 0x0000_0400;
+        const COMPRESSED = 0x0000_0800;
+        const OFFLINE = 0x0000_1000;
+        const NOT_CONTENT_INDEXED = 0x0000_2000;
+        const ENCRYPTED = 0x0000_4000;
+        const INTEGRITY_STREAM = 0x0000_8000;
+        const VIRTUAL = 0x0001_0000;
+        const NO_SCRUB_DATA = 0x0002_0000;
+        const RECALL_ON_OPEN = 0x0004_0000;
+        const PINNED = 0x0008_0000;
+        const UNPINNED = 0x0010_0000;
+        const RECALL_ON_DATA_ACCESS = 0x0040_0000;
+        /// NTFS specific, set on directories (the $I30 index is present).
+        const DUPLICATE_FILE_NAME_INDEX_PRESENT = 0x1000_0000;
+        /// NTFS specific, set on entries with a view index, such as $Secure and $Quota.
+        const VIEW_INDEX_PRESENT = 0x2000_0000;
+    }
+}
+
+impl FileAttributeFlags {
+    /// Converts the flags as stored, keeping the bits which have no named flag, so that
+    /// `bits()` returns the stored value.
+    pub(crate) fn from_raw(flags: u32) -> Self {
+        // Unknown bits are only reported, and never interpreted.
+        unsafe { FileAttributeFlags::from_bits_unchecked(flags) }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StandardInformation {
     pub creation_time: Option<DateTime<Utc>>,
     pub modification_time: Option<DateTime<Utc>>,
     pub access_time: Option<DateTime<Utc>>,
     pub entry_modification_time: Option<DateTime<Utc>>,
-    pub file_attribute_flags: u32,
+    pub file_attribute_flags: FileAttributeFlags,
     pub owner_identifier: u32,
     pub security_descriptor_identifier: u32,
-    pub update_sequence_number: u32,
+    pub update_sequence_number: u64,
+}
+
+/// The namespace of a $FILE_NAME attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileNameNamespace {
+    /// Case sensitive, allows all characters except NUL and `/`.
+    Posix,
+    /// Case insensitive long name.
+    Win32,
+    /// The 8.3 short name which accompanies a Win32 name.
+    Dos,
+    /// A name which is valid both as a Win32 and a DOS name.
+    Win32AndDos,
+    Unknown(u8),
+}
+
+impl From<u8> for FileNameNamespace {
+    fn from(namespace: u8) -> Self {
+        match namespace {
+            0 => FileNameNamespace::Posix,
+            1 => FileNameNamespace::Win32,
+            2 => FileNameNamespace::Dos,
+            3 => FileNameNamespace::Win32AndDos,
+            other => FileNameNamespace::Unknown(other),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FileName {
-    pub name: String,
-    pub parent_file_reference: u64,
+    pub name: NtfsName,
+    pub namespace: FileNameNamespace,
+    pub parent_file_reference: FileReference,
     pub creation_time: Option<DateTime<Utc>>,
     pub modification_time: Option<DateTime<Utc>>,
     pub access_time: Option<DateTime<Utc>>,
     pub entry_modification_time: Option<DateTime<Utc>>,
-    pub file_attribute_flags: u32,
+    pub file_attribute_flags: FileAttributeFlags,
+}
+
+bitflags! {
+    /// The data flags of a non-resident attribute.
+    pub struct DataFlags: u16 {
+        const COMPRESSED = 0x0001;
+        const ENCRYPTED = 0x4000;
+        const SPARSE = 0x8000;
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Data {
-    // TOOD: parse flags
-    pub flags: u32,
-    pub vcn_range_first: u64,
-    pub vcn_range_last: u64,
-    pub size: u32,
+    pub flags: DataFlags,
+    /// The first and last VCN of the attribute, `None` if the data is resident.
+    pub vcn_range: Option<(u64, u64)>,
+    pub size: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -456,13 +543,15 @@ pub struct VolumeInformation {
     pub flags: u16,
 }
 
-#[derive(Debug, Clone)]
-pub struct SecurityDescriptor(Vec<u8>);
-
 #[derive(Debug, Clone)]
 pub struct AttributeList {}
 #[derive(Debug, Clone)]
-pub struct ObjectIdentifier {}
+pub struct ObjectIdentifier {
+    pub droid_file_identifier: Guid,
+    pub birth_droid_volume_identifier: Option<Guid>,
+    pub birth_droid_file_identifier: Option<Guid>,
+    pub birth_droid_domain_identifier: Option<Guid>,
+}
 #[derive(Debug, Clone)]
 pub struct IndexRoot {}
 #[derive(Debug, Clone)]
@@ -470,7 +559,13 @@ pub struct IndexAllocation {}
 #[derive(Debug, Clone)]
 pub struct Bitmap {}
 #[derive(Debug, Clone)]
-pub struct ReparsePoint {}
+pub struct ReparsePoint {
+    pub tag: ReparseTag,
+    /// The target of a symbolic link or junction, as used by the file system.
+    pub substitute_name: Option<String>,
+    /// The target of a symbolic link or junction, as presented to the user.
+    pub print_name: Option<String>,
+}
 #[derive(Debug, Clone)]
 pub struct ExtendedInformation {}
 #[derive(Debug, Clone)]
@@ -487,70 +582,147 @@ impl<'a> Attribute<'a> {
         get_sized_utf8_string!(
             self,
             libfsntfs_attribute_get_utf8_name_size,
-            libfsntfs_attribute_get_utf8_name
+            libfsntfs_attribute_get_utf8_name,
+            self.1.volume()
+        )
+    }
+
+    /// Retrieves the name as the UTF-16 code units stored in the attribute header.
+    pub fn get_name_utf16(&self) -> Result<Vec<u16>, Error> {
+        get_sized_utf16_string!(
+            self,
+            libfsntfs_attribute_get_utf16_name_size,
+            libfsntfs_attribute_get_utf16_name,
+            self.1.volume()
         )
     }
 
+    /// Retrieves the name as an `NtfsName`, which unlike `get_name` also represents names
+    /// with unpaired surrogates.
+    pub fn get_ntfs_name(&self) -> Result<NtfsName, Error> {
+        Ok(NtfsName::from_utf16(self.get_name_utf16()?))
+    }
+
     pub fn get_data(&self) -> Result<AttributeWithInformation, Error> {
         match self.get_type()? {
             AttributeType::VolumeName => {
                 let volume_name = get_sized_utf8_string!(
                     self,
                     libfsntfs_volume_name_attribute_get_utf8_name_size,
-                    libfsntfs_volume_name_attribute_get_utf8_name
+                    libfsntfs_volume_name_attribute_get_utf8_name,
+                    self.1.volume()
                 )?;
 
                 Ok(AttributeWithInformation::VolumeName(volume_name))
             }
             AttributeType::FileName => {
-                let name = get_sized_utf8_string!(
+                let name = NtfsName::from_utf16(get_sized_utf16_string!(
                     self,
-                    libfsntfs_file_name_attribute_get_utf8_name_size,
-                    libfsntfs_file_name_attribute_get_utf8_name
-                )?;
+                    libfsntfs_file_name_attribute_get_utf16_name_size,
+                    libfsntfs_file_name_attribute_get_utf16_name,
+                    self.1.volume()
+                )?);
 
-                let creation_time =
-                    get_date_field!(self, libfsntfs_file_name_attribute_get_creation_time)?;
-                let modification_time =
-                    get_date_field!(self, libfsntfs_file_name_attribute_get_modification_time)?;
-                let access_time =
-                    get_date_field!(self, libfsntfs_file_name_attribute_get_access_time)?;
+                let creation_time = get_date_field!(
+                    self,
+                    libfsntfs_file_name_attribute_get_creation_time,
+                    self.1.volume()
+                )?;
+                let modification_time = get_date_field!(
+                    self,
+                    libfsntfs_file_name_attribute_get_modification_time,
+                    self.1.volume()
+                )?;
+                let access_time = get_date_field!(
+                    self,
+                    libfsntfs_file_name_attribute_get_access_time,
+                    self.1.volume()
+                )?;
                 let entry_modification_time = get_date_field!(
                     self,
-                    libfsntfs_file_name_attribute_get_entry_modification_time
+                    libfsntfs_file_name_attribute_get_entry_modification_time,
+                    self.1.volume()
                 )?;
 
-                let parent_file_reference = get_u64_field!(
+                let mut namespace = 0;
+                let mut error = ptr::null_mut();
+
+                if unsafe {
+                    libfsntfs_file_name_attribute_get_name_space(
+                        self.as_type_ref(),
+                        &mut namespace,
+                        &mut error,
+                    )
+                } != 1
+                {
+                    return Err(self.1.volume().ffi_error(error));
+                }
+
+                let parent_file_reference = FileReference(get_u64_field!(
                     self,
-                    libfsntfs_file_name_attribute_get_parent_file_reference
-                )?;
+                    libfsntfs_file_name_attribute_get_parent_file_reference,
+                    self.1.volume()
+                )?);
+
+                let file_attribute_flags = FileAttributeFlags::from_raw(get_u32_field!(
+                    self,
+                    libfsntfs_file_name_attribute_get_file_attribute_flags,
+                    self.1.volume()
+                )?);
 
                 Ok(AttributeWithInformation::FileName(FileName {
                     name,
+                    namespace: FileNameNamespace::from(namespace),
                     parent_file_reference,
                     creation_time,
                     modification_time,
                     access_time,
                     entry_modification_time,
-                    file_attribute_flags: 0,
+                    file_attribute_flags,
                 }))
             }
             AttributeType::StandardInformation => {
                 let creation_time = get_date_field!(
                     self,
-                    libfsntfs_standard_information_attribute_get_creation_time
+                    libfsntfs_standard_information_attribute_get_creation_time,
+                    self.1.volume()
                 )?;
                 let modification_time = get_date_field!(
                     self,
-                    libfsntfs_standard_information_attribute_get_modification_time
+                    libfsntfs_standard_information_attribute_get_modification_time,
+                    self.1.volume()
                 )?;
                 let access_time = get_date_field!(
                     self,
-                    libfsntfs_standard_information_attribute_get_access_time
+                    libfsntfs_standard_information_attribute_get_access_time,
+                    self.1.volume()
                 )?;
                 let entry_modification_time = get_date_field!(
                     self,
-                    libfsntfs_standard_information_attribute_get_entry_modification_time
+                    libfsntfs_standard_information_attribute_get_entry_modification_time,
+                    self.1.volume()
+                )?;
+
+                let file_attribute_flags = FileAttributeFlags::from_raw(get_u32_field!(
+                    self,
+                    libfsntfs_standard_information_attribute_get_file_attribute_flags,
+                    self.1.volume()
+                )?);
+
+                let owner_identifier = get_u32_field!(
+                    self,
+                    libfsntfs_standard_information_attribute_get_owner_identifier,
+                    self.1.volume()
+                )?;
+                let security_descriptor_identifier = get_u32_field!(
+                    self,
+                    libfsntfs_standard_information_attribute_get_security_descriptor_identifier,
+                    self.1.volume()
+                )?;
+                let update_sequence_number = get_u64_field!(
+                    self,
+                    libfsntfs_standard_information_attribute_get_update_sequence_number,
+                    self.1.volume()
                 )?;
 
                 Ok(AttributeWithInformation::StandardInformation(
@@ -559,29 +731,111 @@ impl<'a> Attribute<'a> {
                         modification_time,
                         access_time,
                         entry_modification_time,
-                        file_attribute_flags: 0,
-                        owner_identifier: 0,
-                        security_descriptor_identifier: 0,
-                        update_sequence_number: 0,
+                        file_attribute_flags,
+                        owner_identifier,
+                        security_descriptor_identifier,
+                        update_sequence_number,
+                    },
+                ))
+            }
+            AttributeType::Data => {
+                let mut flags = 0;
+                let mut error = ptr::null_mut();
+
+                if unsafe {
+                    libfsntfs_attribute_get_data_flags(self.as_type_ref(), &mut flags, &mut error)
+                } != 1
+                {
+                    return Err(self.1.volume().ffi_error(error));
+                }
+
+                let mut vcn_range_first = 0;
+                let mut vcn_range_last = 0;
+                let mut error = ptr::null_mut();
+
+                let vcn_range = match unsafe {
+                    libfsntfs_attribute_get_data_vcn_range(
+                        self.as_type_ref(),
+                        &mut vcn_range_first,
+                        &mut vcn_range_last,
+                        &mut error,
+                    )
+                } {
+                    1 => Some((vcn_range_first, vcn_range_last)),
+                    0 => None,
+                    _ => return Err(self.1.volume().ffi_error(error)),
+                };
+
+                let size =
+                    get_u64_field!(self, libfsntfs_attribute_get_data_size, self.1.volume())?;
+
+                Ok(AttributeWithInformation::Data(Data {
+                    flags: DataFlags::from_bits_truncate(flags),
+                    vcn_range,
+                    size,
+                }))
+            }
+
+            AttributeType::ObjectIdentifier => {
+                let droid_file_identifier = self
+                    .get_guid(libfsntfs_object_identifier_attribute_get_droid_file_identifier)?
+                    .ok_or_else(|| Error::Other("Missing droid file identifier".to_string()))?;
+                let birth_droid_volume_identifier = self.get_guid(
+                    libfsntfs_object_identifier_attribute_get_birth_droid_volume_identifier,
+                )?;
+                let birth_droid_file_identifier = self.get_guid(
+                    libfsntfs_object_identifier_attribute_get_birth_droid_file_identifier,
+                )?;
+                let birth_droid_domain_identifier = self.get_guid(
+                    libfsntfs_object_identifier_attribute_get_birth_droid_domain_identifier,
+                )?;
+
+                Ok(AttributeWithInformation::ObjectIdentifier(
+                    ObjectIdentifier {
+                        droid_file_identifier,
+                        birth_droid_volume_identifier,
+                        birth_droid_file_identifier,
+                        birth_droid_domain_identifier,
                     },
                 ))
             }
-            AttributeType::Data => Ok(AttributeWithInformation::Data(Data {
-                flags: 0,
-                vcn_range_first: 0,
-                vcn_range_last: 0,
-                size: 0,
-            })),
+
+            AttributeType::ReparsePoint => {
+                let tag = get_u32_field!(
+                    self,
+                    libfsntfs_reparse_point_attribute_get_tag,
+                    self.1.volume()
+                )?;
+                let substitute_name = get_optional_sized_utf8_string!(
+                    self,
+                    libfsntfs_reparse_point_attribute_get_utf8_substitute_name_size,
+                    libfsntfs_reparse_point_attribute_get_utf8_substitute_name,
+                    self.1.volume()
+                )?;
+                let print_name = get_optional_sized_utf8_string!(
+                    self,
+                    libfsntfs_reparse_point_attribute_get_utf8_print_name_size,
+                    libfsntfs_reparse_point_attribute_get_utf8_print_name,
+                    self.1.volume()
+                )?;
+
+                Ok(AttributeWithInformation::ReparsePoint(ReparsePoint {
+                    tag: ReparseTag::from(tag),
+                    substitute_name,
+                    print_name,
+                }))
+            }
 
             AttributeType::SecurityDescriptor => {
                 let descriptor = get_sized_bytes!(
                     self,
                     libfsntfs_security_descriptor_attribute_get_security_descriptor_size,
-                    libfsntfs_security_descriptor_attribute_get_security_descriptor
+                    libfsntfs_security_descriptor_attribute_get_security_descriptor,
+                    self.1.volume()
                 )?;
 
                 Ok(AttributeWithInformation::SecurityDescriptor(
-                    SecurityDescriptor(descriptor),
+                    SecurityDescriptor::from_bytes(&descriptor)?,
                 ))
             }
             _ => Err(Error::Other(format!(
@@ -591,6 +845,33 @@ impl<'a> Attribute<'a> {
         }
     }
 
+    /// Reads one of the object identifier GUIDs, which are optional unless noted otherwise.
+    fn get_guid(
+        &self,
+        get_guid: unsafe extern "C" fn(
+            AttributeRef,
+            *mut u8,
+            usize,
+            *mut LibfsntfsErrorRefMut,
+        ) -> c_int,
+    ) -> Result<Option<Guid>, Error> {
+        let mut guid = [0; 16];
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            get_guid(
+                self.as_type_ref(),
+                guid.as_mut_ptr(),
+                guid.len(),
+                &mut error,
+            )
+        } {
+            1 => Ok(Some(Guid::from_bytes(guid))),
+            0 => Ok(None),
+            _ => Err(self.1.volume().ffi_error(error)),
+        }
+    }
+
     pub fn get_type(&self) -> Result<AttributeType, Error> {
         let mut type_as_num = 0_u32;
         let mut error = ptr::null_mut();
@@ -598,7 +879,7 @@ impl<'a> Attribute<'a> {
         if unsafe { libfsntfs_attribute_get_type(self.as_type_ref(), &mut type_as_num, &mut error) }
             != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.volume().ffi_error(error))
         } else {
             Ok(AttributeType::try_from(type_as_num)?)
         }
@@ -0,0 +1,315 @@
+use crate::error::Error;
+use crate::extent::{get_extent_by_index, Extent, IterExtents};
+use crate::ffi_error::LibfsntfsErrorRefMut;
+use crate::file_entry::FileEntry;
+use crate::name::NtfsName;
+use libfsntfs_sys::{off64_t, size64_t, SEEK_CUR, SEEK_END, SEEK_SET};
+use libyal_rs_common::ffi::AsTypeRef;
+use std::ffi::c_void;
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::raw::c_int;
+use std::{fmt, io, ptr};
+
+#[repr(C)]
+pub struct __DataStream(isize);
+
+pub type DataStreamRefMut = *mut __DataStream;
+pub type DataStreamRef = *const __DataStream;
+
+#[repr(C)]
+pub struct DataStream<'a>(DataStreamRefMut, &'a FileEntry<'a>);
+
+impl<'a> AsTypeRef for DataStream<'a> {
+    type Ref = DataStreamRef;
+    type RefMut = DataStreamRefMut;
+
+    #[inline]
+    fn as_type_ref(&self) -> Self::Ref {
+        // https://users.rust-lang.org/t/is-it-ub-to-convert-t-to-mut-t/16238/4
+        self.0 as *const _
+    }
+
+    #[inline]
+    fn as_type_ref_mut(&mut self) -> Self::RefMut {
+        self.0
+    }
+
+    #[inline]
+    fn as_raw(&mut self) -> *mut Self::RefMut {
+        &mut self.0 as *mut _
+    }
+}
+
+impl<'a> DataStream<'a> {
+    pub fn wrap_ptr(file_entry: &'a FileEntry<'a>, ptr: DataStreamRefMut) -> Self {
+        DataStream(ptr, file_entry)
+    }
+}
+
+impl<'a> Drop for DataStream<'a> {
+    fn drop(&mut self) {
+        use log::trace;
+
+        let mut error = ptr::null_mut();
+
+        trace!("Calling `libfsntfs_data_stream_free`");
+
+        unsafe {
+            libfsntfs_data_stream_free(self.as_raw(), &mut error);
+        }
+
+        debug_assert!(error.is_null(), "`libfsntfs_data_stream_free` failed!");
+    }
+}
+
+impl<'a> Debug for DataStream<'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("DataStream")
+            .field("Name", &self.get_name().unwrap_or("".to_string()))
+            .field("Size", &self.get_size().unwrap_or(0))
+            .finish()
+    }
+}
+
+extern "C" {
+    pub fn libfsntfs_data_stream_free(
+        data_stream: *mut DataStreamRefMut,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_utf8_name_size(
+        data_stream: DataStreamRef,
+        utf8_name_size: *mut usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_utf8_name(
+        data_stream: DataStreamRef,
+        utf8_name: *mut u8,
+        utf8_name_size: usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_utf16_name_size(
+        data_stream: DataStreamRef,
+        utf16_name_size: *mut usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_utf16_name(
+        data_stream: DataStreamRef,
+        utf16_name: *mut u16,
+        utf16_name_size: usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_read_buffer(
+        data_stream: DataStreamRef,
+        buffer: *mut c_void,
+        buffer_size: usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> isize;
+    pub fn libfsntfs_data_stream_read_buffer_at_offset(
+        data_stream: DataStreamRef,
+        buffer: *mut c_void,
+        buffer_size: usize,
+        offset: off64_t,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> isize;
+    pub fn libfsntfs_data_stream_seek_offset(
+        data_stream: DataStreamRef,
+        offset: off64_t,
+        whence: c_int,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> off64_t;
+    pub fn libfsntfs_data_stream_get_offset(
+        data_stream: DataStreamRef,
+        offset: *mut off64_t,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_size(
+        data_stream: DataStreamRef,
+        size: *mut size64_t,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_number_of_extents(
+        data_stream: DataStreamRef,
+        number_of_extents: *mut c_int,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_data_stream_get_extent_by_index(
+        data_stream: DataStreamRef,
+        extent_index: c_int,
+        extent_offset: *mut off64_t,
+        extent_size: *mut size64_t,
+        extent_flags: *mut u32,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+}
+
+impl<'a> Read for DataStream<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.1.volume().is_aborted() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", Error::Aborted),
+            ));
+        }
+
+        let mut error = ptr::null_mut();
+        let read_count = unsafe {
+            libfsntfs_data_stream_read_buffer(
+                self.as_type_ref(),
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len(),
+                &mut error,
+            )
+        };
+
+        if read_count <= -1 {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", self.1.volume().ffi_error(error)),
+            ))
+        } else {
+            Ok(read_count as usize)
+        }
+    }
+}
+
+impl<'a> Seek for DataStream<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
+        let mut error = ptr::null_mut();
+
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as i64, SEEK_SET),
+            SeekFrom::End(offset) => (offset, SEEK_END),
+            SeekFrom::Current(offset) => (offset, SEEK_CUR),
+        };
+
+        let seek_pos = unsafe {
+            libfsntfs_data_stream_seek_offset(
+                self.as_type_ref(),
+                offset,
+                whence as c_int,
+                &mut error,
+            )
+        };
+
+        if seek_pos <= -1 {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", self.1.volume().ffi_error(error)),
+            ))
+        } else {
+            Ok(seek_pos as u64)
+        }
+    }
+}
+
+impl<'a> DataStream<'a> {
+    /// Retrieves the name of the stream.
+    pub fn get_name(&self) -> Result<String, Error> {
+        get_sized_utf8_string!(
+            self,
+            libfsntfs_data_stream_get_utf8_name_size,
+            libfsntfs_data_stream_get_utf8_name,
+            self.1.volume()
+        )
+    }
+
+    /// Retrieves the raw UTF-16 name of the stream.
+    pub fn get_name_utf16(&self) -> Result<Vec<u16>, Error> {
+        get_sized_utf16_string!(
+            self,
+            libfsntfs_data_stream_get_utf16_name_size,
+            libfsntfs_data_stream_get_utf16_name,
+            self.1.volume()
+        )
+    }
+
+    /// Retrieves the name of the stream as an `NtfsName`.
+    pub fn get_ntfs_name(&self) -> Result<NtfsName, Error> {
+        Ok(NtfsName::from_utf16(self.get_name_utf16()?))
+    }
+
+    /// Retrieves the size of the data.
+    pub fn get_size(&self) -> Result<u64, Error> {
+        get_u64_field!(self, libfsntfs_data_stream_get_size, self.1.volume())
+    }
+
+    pub fn get_number_of_extents(&self) -> Result<c_int, Error> {
+        let mut number_of_extents = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_data_stream_get_number_of_extents(
+                self.as_type_ref(),
+                &mut number_of_extents,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.volume().ffi_error(error))
+        } else {
+            Ok(number_of_extents)
+        }
+    }
+
+    pub fn get_extent(&self, extent_index: c_int) -> Result<Extent, Error> {
+        get_extent_by_index(
+            self.1.volume(),
+            self.as_type_ref(),
+            libfsntfs_data_stream_get_extent_by_index,
+            extent_index,
+            self.1.is_allocated()?,
+        )
+    }
+
+    pub fn iter_extents(&self) -> Result<IterExtents<DataStreamRef>, Error> {
+        Ok(IterExtents::new(
+            self.1.volume(),
+            self.as_type_ref(),
+            libfsntfs_data_stream_get_extent_by_index,
+            self.get_number_of_extents()?,
+            self.1.is_allocated()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_read_named_stream() {
+        let volume = sample_volume().unwrap();
+        // $Secure keeps its security descriptors in the `$SDS` stream.
+        let secure = volume.get_file_entry_by_mft_idx(9).unwrap();
+
+        let mut stream = secure
+            .get_alternate_data_stream_by_name("$SDS")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(stream.get_name().unwrap(), "$SDS");
+
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).unwrap();
+
+        assert_eq!(buf.len() as u64, stream.get_size().unwrap());
+    }
+
+    #[test]
+    fn test_iter_extents() {
+        let volume = sample_volume().unwrap();
+        let secure = volume.get_file_entry_by_mft_idx(9).unwrap();
+        let stream = secure
+            .get_alternate_data_stream_by_name("$SDS")
+            .unwrap()
+            .unwrap();
+
+        let extents: Vec<Extent> = stream.iter_extents().unwrap().map(|e| e.unwrap()).collect();
+        let extents_size: u64 = extents.iter().map(|e| e.size).sum();
+
+        assert!(!extents.is_empty());
+        assert!(extents_size >= stream.get_size().unwrap());
+    }
+}
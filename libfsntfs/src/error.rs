@@ -23,6 +23,18 @@ pub enum Error {
     FailedToConvertFromBytes(#[cause] FromBytesWithNulError),
     #[fail(display = "String contains NUL where is it not allowed: {}", _0)]
     StringContainsNul(#[cause] NulError),
+    #[fail(display = "The operation was aborted")]
+    Aborted,
+    #[fail(display = "Invalid USN record: {}", _0)]
+    InvalidUsnRecord(String),
+    #[fail(display = "Invalid security descriptor: {}", _0)]
+    InvalidSecurityDescriptor(String),
+    #[fail(display = "The volume is BitLocker encrypted")]
+    BitLockerEncrypted,
+    #[fail(display = "An IO error has occurred: {}", _0)]
+    Io(#[cause] std::io::Error),
+    #[fail(display = "An IO handle error has occurred: {}", _0)]
+    IoHandle(#[cause] libbfio_rs::error::Error),
     #[fail(display = "An FFI error has occurred: {}", _0)]
     FFI(String),
     #[fail(display = "An unexpected error has occurred: {}", _0)]
@@ -0,0 +1,125 @@
+use crate::error::Error;
+use crate::ffi_error::LibfsntfsErrorRefMut;
+use crate::volume::Volume;
+use bitflags::bitflags;
+use libfsntfs_sys::{off64_t, size64_t};
+use std::os::raw::c_int;
+use std::ptr;
+
+bitflags! {
+    /// Describes how the data of an extent is stored (`LIBFSNTFS_EXTENT_FLAG_*`).
+    pub struct ExtentFlags: u32 {
+        const SPARSE = 0x0000_0001;
+        const COMPRESSED = 0x0000_0002;
+        /// Not reported by libfsntfs, set when the owning file entry is not allocated,
+        /// in which case the clusters may have been reused since.
+        const UNALLOCATED = 0x8000_0000;
+    }
+}
+
+/// A range of the volume which backs (part of) a data stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extent {
+    /// Offset relative to the start of the volume.
+    pub offset: u64,
+    pub size: u64,
+    pub flags: ExtentFlags,
+}
+
+/// A `libfsntfs_*_get_extent_by_index` function of the file entry or data stream `R`.
+pub(crate) type GetExtentByIndex<R> = unsafe extern "C" fn(
+    R,
+    c_int,
+    *mut off64_t,
+    *mut size64_t,
+    *mut u32,
+    *mut LibfsntfsErrorRefMut,
+) -> c_int;
+
+/// Retrieves the extent at `extent_index` of `handle`, which is marked as unallocated
+/// unless `is_allocated`.
+pub(crate) fn get_extent_by_index<R>(
+    volume: &Volume,
+    handle: R,
+    get_extent_by_index: GetExtentByIndex<R>,
+    extent_index: c_int,
+    is_allocated: bool,
+) -> Result<Extent, Error> {
+    let mut offset = 0;
+    let mut size = 0;
+    let mut flags = 0;
+    let mut error = ptr::null_mut();
+
+    if unsafe {
+        get_extent_by_index(
+            handle,
+            extent_index,
+            &mut offset,
+            &mut size,
+            &mut flags,
+            &mut error,
+        )
+    } != 1
+    {
+        Err(volume.ffi_error(error))
+    } else {
+        let mut flags = ExtentFlags::from_bits_truncate(flags);
+        flags.set(ExtentFlags::UNALLOCATED, !is_allocated);
+
+        Ok(Extent {
+            offset: offset as u64,
+            size,
+            flags,
+        })
+    }
+}
+
+/// Iterates over the extents of a file entry or data stream, whose reference is `R`.
+pub struct IterExtents<'a, R> {
+    volume: &'a Volume,
+    handle: R,
+    get_extent_by_index: GetExtentByIndex<R>,
+    num_extents: c_int,
+    is_allocated: bool,
+    idx: c_int,
+}
+
+impl<'a, R> IterExtents<'a, R> {
+    pub(crate) fn new(
+        volume: &'a Volume,
+        handle: R,
+        get_extent_by_index: GetExtentByIndex<R>,
+        num_extents: c_int,
+        is_allocated: bool,
+    ) -> Self {
+        IterExtents {
+            volume,
+            handle,
+            get_extent_by_index,
+            num_extents,
+            is_allocated,
+            idx: 0,
+        }
+    }
+}
+
+impl<'a, R: Copy> Iterator for IterExtents<'a, R> {
+    type Item = Result<Extent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx < self.num_extents {
+            let extent = get_extent_by_index(
+                self.volume,
+                self.handle,
+                self.get_extent_by_index,
+                self.idx,
+                self.is_allocated,
+            );
+            self.idx += 1;
+
+            return Some(extent);
+        }
+
+        None
+    }
+}
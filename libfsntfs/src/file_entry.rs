@@ -1,14 +1,22 @@
 use chrono::prelude::*;
 
-use crate::attribute::{Attribute, AttributeRef, AttributeRefMut};
+use crate::attribute::{
+    Attribute, AttributeRef, AttributeRefMut, AttributeType, AttributeWithInformation,
+    FileAttributeFlags, FileName,
+};
+use crate::data_stream::{DataStream, DataStreamRefMut};
 use crate::error::Error;
+use crate::extent::{get_extent_by_index, Extent, ExtentFlags, IterExtents};
 use crate::ffi_error::{LibfsntfsError, LibfsntfsErrorRef, LibfsntfsErrorRefMut};
-use crate::volume::{Volume, VolumeRef};
-use libfsntfs_sys::{
-    libfsntfs_attribute_t, libfsntfs_data_stream_t, off64_t, size64_t, SEEK_CUR, SEEK_END, SEEK_SET,
-};
+use crate::file_reference::FileReference;
+use crate::hash::digest_stream;
+use crate::name::NtfsName;
+use crate::security_descriptor::SecurityDescriptor;
+use crate::upcase::UpcaseTable;
+use crate::volume::{Volume, VolumeRef, ROOT_DIRECTORY_INDEX};
+use digest::Digest;
+use libfsntfs_sys::{libfsntfs_attribute_t, off64_t, size64_t, SEEK_CUR, SEEK_END, SEEK_SET};
 use libyal_rs_common::ffi::AsTypeRef;
-use std::convert::TryFrom;
 use std::ffi::c_void;
 use std::fmt::{Debug, Formatter};
 use std::fs::read;
@@ -52,6 +60,10 @@ impl<'a> FileEntry<'a> {
     pub fn wrap_ptr(volume: &'a Volume, ptr: FileEntryRefMut) -> Self {
         FileEntry(ptr, volume)
     }
+
+    pub(crate) fn volume(&self) -> &'a Volume {
+        self.1
+    }
 }
 
 impl<'a> Drop for FileEntry<'a> {
@@ -278,7 +290,7 @@ extern "C" {
     pub fn libfsntfs_file_entry_get_alternate_data_stream_by_index(
         file_entry: FileEntryRef,
         alternate_data_stream_index: c_int,
-        alternate_data_stream: *mut *mut libfsntfs_data_stream_t,
+        alternate_data_stream: *mut DataStreamRefMut,
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
     pub fn libfsntfs_file_entry_has_alternate_data_stream_by_utf8_name(
@@ -297,14 +309,14 @@ extern "C" {
         file_entry: FileEntryRef,
         utf8_string: *const u8,
         utf8_string_length: usize,
-        alternate_data_stream: *mut *mut libfsntfs_data_stream_t,
+        alternate_data_stream: *mut DataStreamRefMut,
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
     pub fn libfsntfs_file_entry_get_alternate_data_stream_by_utf16_name(
         file_entry: FileEntryRef,
         utf16_string: *const u16,
         utf16_string_length: usize,
-        alternate_data_stream: *mut *mut libfsntfs_data_stream_t,
+        alternate_data_stream: *mut DataStreamRefMut,
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
     pub fn libfsntfs_file_entry_get_number_of_sub_file_entries(
@@ -397,6 +409,27 @@ impl<'a> Iterator for IterAttributes<'a> {
     }
 }
 
+pub struct IterAlternateDataStreams<'a> {
+    handle: &'a FileEntry<'a>,
+    num_alternate_data_streams: u32,
+    idx: u32,
+}
+
+impl<'a> Iterator for IterAlternateDataStreams<'a> {
+    type Item = Result<DataStream<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx < self.num_alternate_data_streams {
+            let stream = self.handle.get_alternate_data_stream(self.idx as i32);
+            self.idx += 1;
+
+            return Some(stream);
+        }
+
+        None
+    }
+}
+
 pub struct IterSubEntries<'a: 'b, 'b> {
     handle: &'b FileEntry<'a>,
     num_sub_entries: u32,
@@ -418,8 +451,51 @@ impl<'a: 'b, 'b> Iterator for IterSubEntries<'a, 'b> {
     }
 }
 
+/// Returns the first of `candidates` with a name which equals `name` under `upcase_table`.
+fn find_by_name<T, I>(
+    candidates: I,
+    upcase_table: &UpcaseTable,
+    name: &[u16],
+) -> Result<Option<T>, Error>
+where
+    I: IntoIterator<Item = Result<(T, Vec<NtfsName>), Error>>,
+{
+    for candidate in candidates {
+        let (candidate, names) = candidate?;
+
+        if names
+            .iter()
+            .any(|candidate_name| upcase_table.eq_ignore_case(candidate_name.as_utf16(), name))
+        {
+            return Ok(Some(candidate));
+        }
+    }
+
+    Ok(None)
+}
+
 impl<'a> Read for FileEntry<'a> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        (&*self).read(buf)
+    }
+}
+
+impl<'a> Seek for FileEntry<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
+        (&*self).seek(pos)
+    }
+}
+
+/// Like `&File`, reads and seeks through a shared reference move the one offset of the entry.
+impl<'a, 'b> Read for &'b FileEntry<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.1.is_aborted() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", Error::Aborted),
+            ));
+        }
+
         let mut error = ptr::null_mut();
         let read_count = unsafe {
             libfsntfs_file_entry_read_buffer(
@@ -431,24 +507,17 @@ impl<'a> Read for FileEntry<'a> {
         };
 
         if read_count <= -1 {
-            let ffi_err = Error::try_from(error);
-
-            let io_err = match ffi_err {
-                Ok(e) => io::Error::new(io::ErrorKind::Other, format!("{}", e)),
-                Err(e) => io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("error while getting error information"),
-                ),
-            };
-
-            Err(io_err)
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", self.1.ffi_error(error)),
+            ))
         } else {
             Ok(read_count as usize)
         }
     }
 }
 
-impl<'a> Seek for FileEntry<'a> {
+impl<'a, 'b> Seek for &'b FileEntry<'a> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
         let mut error = ptr::null_mut();
 
@@ -480,17 +549,10 @@ impl<'a> Seek for FileEntry<'a> {
         };
 
         if seek_pos <= -1 {
-            let ffi_err = Error::try_from(error);
-
-            let io_err = match ffi_err {
-                Ok(e) => io::Error::new(io::ErrorKind::Other, format!("{}", e)),
-                Err(e) => io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("error while getting error information"),
-                ),
-            };
-
-            Err(io_err)
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{}", self.1.ffi_error(error)),
+            ))
         } else {
             Ok(seek_pos as u64)
         }
@@ -499,8 +561,13 @@ impl<'a> Seek for FileEntry<'a> {
 
 impl<'a> FileEntry<'a> {
     /// Returns the access date and time.
-    pub fn get_access_time(&self) -> Option<DateTime<Utc>> {
-        unimplemented!();
+    pub fn get_access_time(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        get_date_field!(self, libfsntfs_file_entry_get_access_time, self.1)
+    }
+
+    /// Returns the access date and time as a FILETIME.
+    pub fn get_access_time_as_integer(&self) -> Result<u64, Error> {
+        get_u64_field!(self, libfsntfs_file_entry_get_access_time, self.1)
     }
 
     pub fn get_size(&self) -> Result<u64, Error> {
@@ -509,20 +576,78 @@ impl<'a> FileEntry<'a> {
 
         if unsafe { libfsntfs_file_entry_get_size(self.as_type_ref(), &mut size, &mut error) } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
             Ok(size)
         }
     }
 
     /// Retrieves a specific alternate data stream.
-    pub fn get_alternate_data_stream(&self, alternate_data_stream_index: isize) {
-        unimplemented!();
+    pub fn get_alternate_data_stream(
+        &self,
+        alternate_data_stream_index: i32,
+    ) -> Result<DataStream, Error> {
+        let mut data_stream = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_alternate_data_stream_by_index(
+                self.as_type_ref(),
+                alternate_data_stream_index,
+                &mut data_stream,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(DataStream::wrap_ptr(self, data_stream))
+        }
     }
 
     /// Retrieves an alternate data stream specified by the name.
-    pub fn get_alternate_data_stream_by_name(&self, name: isize) {
-        unimplemented!();
+    /// Returns `None` if the file entry has no stream with that name.
+    pub fn get_alternate_data_stream_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<DataStream>, Error> {
+        let mut data_stream = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_get_alternate_data_stream_by_utf8_name(
+                self.as_type_ref(),
+                name.as_ptr(),
+                name.len(),
+                &mut data_stream,
+                &mut error,
+            )
+        } {
+            1 => Ok(Some(DataStream::wrap_ptr(self, data_stream))),
+            0 => Ok(None),
+            _ => Err(self.1.ffi_error(error)),
+        }
+    }
+
+    pub fn iter_alternate_data_streams(&self) -> Result<IterAlternateDataStreams, Error> {
+        let number_of_alternate_data_streams = self.get_number_of_alternate_data_streams()? as u32;
+
+        Ok(IterAlternateDataStreams {
+            handle: self,
+            num_alternate_data_streams: number_of_alternate_data_streams,
+            idx: 0,
+        })
+    }
+
+    /// Iterates over the extents of the default data stream.
+    pub fn iter_extents(&self) -> Result<IterExtents<FileEntryRef>, Error> {
+        Ok(IterExtents::new(
+            self.1,
+            self.as_type_ref(),
+            libfsntfs_file_entry_get_extent_by_index,
+            self.get_number_of_extents()?,
+            self.is_allocated()?,
+        ))
     }
 
     pub fn iter_attributes(&self) -> Result<IterAttributes, Error> {
@@ -557,7 +682,7 @@ impl<'a> FileEntry<'a> {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
             Ok(num_attributes)
         }
@@ -576,7 +701,7 @@ impl<'a> FileEntry<'a> {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
             Ok(Attribute::wrap_ptr(self, attribute))
         }
@@ -586,10 +711,69 @@ impl<'a> FileEntry<'a> {
         get_sized_utf8_string!(
             self,
             libfsntfs_file_entry_get_utf8_name_size,
-            libfsntfs_file_entry_get_utf8_name
+            libfsntfs_file_entry_get_utf8_name,
+            self.1
+        )
+    }
+
+    /// Retrieves the name as it is stored, including unpaired surrogates which `get_name`
+    /// fails to convert to UTF-8.
+    pub fn get_name_utf16(&self) -> Result<Vec<u16>, Error> {
+        get_sized_utf16_string!(
+            self,
+            libfsntfs_file_entry_get_utf16_name_size,
+            libfsntfs_file_entry_get_utf16_name,
+            self.1
         )
     }
 
+    /// Retrieves the name as an `NtfsName`, for lossless, lossy or escaped conversions.
+    pub fn get_ntfs_name(&self) -> Result<NtfsName, Error> {
+        Ok(NtfsName::from_utf16(self.get_name_utf16()?))
+    }
+
+    /// Retrieves the sub file entry with the given name, which is compared case-insensitively
+    /// using the volume's `$UpCase` table. Both long and short (DOS) names are matched.
+    ///
+    /// The directory index is searched first, but libfsntfs only folds the case of ASCII
+    /// characters there. When a name with other characters is not found, the names of every
+    /// sub file entry are compared instead, which is linear in the size of the directory.
+    pub fn get_sub_file_entry_by_name(&self, name: &str) -> Result<Option<FileEntry<'a>>, Error> {
+        let mut sub_entry = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_get_sub_file_entry_by_utf8_name(
+                self.as_type_ref(),
+                name.as_ptr(),
+                name.len(),
+                &mut sub_entry,
+                &mut error,
+            )
+        } {
+            1 => return Ok(Some(FileEntry::wrap_ptr(self.1, sub_entry))),
+            0 => {}
+            _ => return Err(self.1.ffi_error(error)),
+        };
+
+        // libfsntfs folds case itself, which agrees with $UpCase for ASCII names.
+        if name.is_ascii() {
+            return Ok(None);
+        }
+
+        let upcase_table = self.1.get_upcase_table()?;
+        let name: Vec<u16> = name.encode_utf16().collect();
+
+        let candidates = self.iter_sub_entries()?.map(|sub_entry| {
+            let sub_entry = sub_entry?;
+            let names = sub_entry.names()?.into_iter().map(|n| n.name).collect();
+
+            Ok((sub_entry, names))
+        });
+
+        find_by_name(candidates, &upcase_table, &name)
+    }
+
     pub fn get_sub_file_entry(&self, sub_file_entry_index: i32) -> Result<FileEntry<'a>, Error> {
         let mut sub_entry = ptr::null_mut();
         let mut error = ptr::null_mut();
@@ -603,7 +787,7 @@ impl<'a> FileEntry<'a> {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
             Ok(FileEntry::wrap_ptr(self.1, sub_entry))
         }
@@ -621,134 +805,433 @@ impl<'a> FileEntry<'a> {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
             Ok(number_of_file_entries)
         }
     }
 
-    pub fn get_parent_file_reference(&self) -> Result<u64, Error> {
+    pub fn get_parent_file_reference(&self) -> Result<FileReference, Error> {
+        self.get_optional_parent_file_reference()?
+            .ok_or_else(|| Error::Other("File entry has no parent file reference".to_owned()))
+    }
+
+    /// Returns `None` for entries without a $FILE_NAME attribute.
+    pub(crate) fn get_optional_parent_file_reference(
+        &self,
+    ) -> Result<Option<FileReference>, Error> {
         let mut parent_file_idx = 0;
         let mut error = ptr::null_mut();
 
-        if unsafe {
+        match unsafe {
             libfsntfs_file_entry_get_parent_file_reference(
                 self.as_type_ref(),
                 &mut parent_file_idx,
                 &mut error,
             )
+        } {
+            1 => Ok(Some(FileReference(parent_file_idx))),
+            0 => Ok(None),
+            _ => Err(self.1.ffi_error(error)),
+        }
+    }
+
+    /// Builds the full path of the entry from the root directory, separated by `/`.
+    /// Names are escaped with `NtfsName::escape`, as in the paths yielded by `Volume::walk`.
+    ///
+    /// Entries whose parent directory no longer exists are reported under `/$OrphanFiles`.
+    /// Returns `None` for entries without a name, such as MFT extension records.
+    pub fn full_path(&self) -> Result<Option<String>, Error> {
+        let parent_file_reference = match self.get_optional_parent_file_reference()? {
+            Some(parent_file_reference) => parent_file_reference,
+            None => return Ok(None),
+        };
+
+        if self.get_file_reference()?.entry_index() == ROOT_DIRECTORY_INDEX {
+            return Ok(Some("/".to_owned()));
+        }
+
+        let name = self.get_ntfs_name()?.escape();
+        let path = self.1.resolve_path(name, parent_file_reference)?;
+
+        Ok(Some(path))
+    }
+
+    pub fn get_file_reference(&self) -> Result<FileReference, Error> {
+        let mut file_idx = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_file_reference(self.as_type_ref(), &mut file_idx, &mut error)
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.1.ffi_error(error))
         } else {
-            Ok(parent_file_idx)
+            Ok(FileReference(file_idx))
         }
     }
 
-    pub fn get_file_reference(&self) -> Result<u64, Error> {
-        let mut file_idx = 0;
+    /// Retrieves the reference of the base record, if this entry is an extension record.
+    pub fn get_base_record_file_reference(&self) -> Result<Option<FileReference>, Error> {
+        let mut file_reference = 0;
         let mut error = ptr::null_mut();
 
-        if unsafe {
-            libfsntfs_file_entry_get_file_reference(
+        match unsafe {
+            libfsntfs_file_entry_get_base_record_file_reference(
                 self.as_type_ref(),
-                &mut file_idx,
+                &mut file_reference,
                 &mut error,
             )
-        } != 1
-        {
-            Err(Error::try_from(error)?)
-        } else {
-            Ok(file_idx)
+        } {
+            1 if file_reference != 0 => Ok(Some(FileReference(file_reference))),
+            1 | 0 => Ok(None),
+            _ => Err(self.1.ffi_error(error)),
         }
     }
 
-    pub fn get_base_record_file_reference(&self) {
-        unimplemented!();
+    /// Returns the creation date and time.
+    pub fn get_creation_time(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        get_date_field!(self, libfsntfs_file_entry_get_creation_time, self.1)
     }
 
-    pub fn get_creation_time(&self) {
-        unimplemented!();
+    /// Returns the creation date and time as a FILETIME.
+    pub fn get_creation_time_as_integer(&self) -> Result<u64, Error> {
+        get_u64_field!(self, libfsntfs_file_entry_get_creation_time, self.1)
     }
 
-    pub fn get_creation_time_as_integer(&self) {
-        unimplemented!();
+    /// Returns the (file system) entry modification date and time.
+    pub fn get_entry_modification_time(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        get_date_field!(
+            self,
+            libfsntfs_file_entry_get_entry_modification_time,
+            self.1
+        )
     }
 
-    pub fn get_entry_modification_time(&self) {
-        unimplemented!();
+    /// Returns the (file system) entry modification date and time as a FILETIME.
+    pub fn get_entry_modification_time_as_integer(&self) -> Result<u64, Error> {
+        get_u64_field!(
+            self,
+            libfsntfs_file_entry_get_entry_modification_time,
+            self.1
+        )
     }
 
-    pub fn get_entry_modification_time_as_integer(&self) {
-        unimplemented!();
+    /// Retrieves an extent of the default data stream.
+    pub fn get_extent(&self, extent_index: c_int) -> Result<Extent, Error> {
+        get_extent_by_index(
+            self.1,
+            self.as_type_ref(),
+            libfsntfs_file_entry_get_extent_by_index,
+            extent_index,
+            self.is_allocated()?,
+        )
     }
 
-    pub fn get_extent(&self, extent_index: isize) {
-        unimplemented!();
+    /// Returns the file attribute flags.
+    pub fn get_file_attribute_flags(&self) -> Result<FileAttributeFlags, Error> {
+        let flags = get_u32_field!(self, libfsntfs_file_entry_get_file_attribute_flags, self.1)?;
+
+        Ok(FileAttributeFlags::from_raw(flags))
     }
 
-    pub fn get_file_attribute_flags(&self) {
-        unimplemented!();
+    /// Returns the journal sequence number ($LogFile sequence number).
+    pub fn get_journal_sequence_number(&self) -> Result<u64, Error> {
+        get_u64_field!(
+            self,
+            libfsntfs_file_entry_get_journal_sequence_number,
+            self.1
+        )
     }
 
-    pub fn get_journal_sequence_number(&self) {
-        unimplemented!();
+    /// Returns the modification date and time.
+    pub fn get_modification_time(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        get_date_field!(self, libfsntfs_file_entry_get_modification_time, self.1)
     }
 
-    pub fn get_modification_time(&self) {
-        unimplemented!();
+    /// Returns the modification date and time as a FILETIME.
+    pub fn get_modification_time_as_integer(&self) -> Result<u64, Error> {
+        get_u64_field!(self, libfsntfs_file_entry_get_modification_time, self.1)
     }
 
-    pub fn get_modification_time_as_integer(&self) {
-        unimplemented!();
+    /// Retrieves the index of the $FILE_NAME attribute which `get_name` returns.
+    pub fn get_name_attribute_index(&self) -> Result<c_int, Error> {
+        let mut attribute_index = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_name_attribute_index(
+                self.as_type_ref(),
+                &mut attribute_index,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(attribute_index)
+        }
     }
 
-    pub fn get_name_attribute_index(&self) {
-        unimplemented!();
+    /// Retrieves the name of the $FILE_NAME attribute at `attribute_index`.
+    pub fn get_name_by_attribute_index(&self, attribute_index: c_int) -> Result<String, Error> {
+        let mut name_size = 0_usize;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_utf8_name_size_by_attribute_index(
+                self.as_type_ref(),
+                attribute_index,
+                &mut name_size,
+                &mut error,
+            )
+        } != 1
+        {
+            return Err(self.1.ffi_error(error));
+        }
+
+        if name_size == 0 {
+            return Ok(String::new());
+        }
+
+        let mut name = vec![0; name_size];
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_utf8_name_by_attribute_index(
+                self.as_type_ref(),
+                attribute_index,
+                name.as_mut_ptr(),
+                name.len(),
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            // Discard nul terminator;
+            name.pop().expect("name_size was checked to be > 0");
+            String::from_utf8(name).map_err(Error::StringContainsInvalidUTF8)
+        }
     }
 
-    pub fn get_name_by_attribute_index(&self, attribute_index: isize) {
-        unimplemented!();
+    /// Retrieves every $FILE_NAME of the entry, i.e. its short (DOS) name and any hard links.
+    pub fn names(&self) -> Result<Vec<FileName>, Error> {
+        let mut names = Vec::new();
+
+        for attribute in self.iter_attributes()? {
+            let attribute = attribute?;
+
+            if attribute.get_type()? != AttributeType::FileName {
+                continue;
+            }
+
+            if let AttributeWithInformation::FileName(name) = attribute.get_data()? {
+                names.push(name);
+            }
+        }
+
+        Ok(names)
     }
 
-    pub fn get_number_of_alternate_data_streams(&self) {
-        unimplemented!();
+    pub fn get_number_of_alternate_data_streams(&self) -> Result<c_int, Error> {
+        let mut number_of_alternate_data_streams = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_number_of_alternate_data_streams(
+                self.as_type_ref(),
+                &mut number_of_alternate_data_streams,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(number_of_alternate_data_streams)
+        }
     }
 
-    pub fn get_number_of_extents(&self) {
-        unimplemented!();
+    pub fn get_number_of_extents(&self) -> Result<c_int, Error> {
+        let mut number_of_extents = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_number_of_extents(
+                self.as_type_ref(),
+                &mut number_of_extents,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(number_of_extents)
+        }
     }
 
-    pub fn get_parent_file_reference_by_attribute_index(&self, attribute_index: isize) {
-        unimplemented!();
+    /// Retrieves the parent reference of the $FILE_NAME attribute at `attribute_index`.
+    pub fn get_parent_file_reference_by_attribute_index(
+        &self,
+        attribute_index: c_int,
+    ) -> Result<FileReference, Error> {
+        let mut file_reference = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_parent_file_reference_by_attribute_index(
+                self.as_type_ref(),
+                attribute_index,
+                &mut file_reference,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(FileReference(file_reference))
+        }
     }
 
-    pub fn get_reparse_point_print_name(&self) {
-        unimplemented!();
+    /// Retrieves the print name of the reparse point, if the entry has one.
+    pub fn get_reparse_point_print_name(&self) -> Result<Option<String>, Error> {
+        get_optional_sized_utf8_string!(
+            self,
+            libfsntfs_file_entry_get_utf8_reparse_point_print_name_size,
+            libfsntfs_file_entry_get_utf8_reparse_point_print_name,
+            self.1
+        )
     }
 
-    pub fn get_reparse_point_substitute_name(&self) {
-        unimplemented!();
+    /// Retrieves the substitute name of the reparse point, if the entry has one.
+    pub fn get_reparse_point_substitute_name(&self) -> Result<Option<String>, Error> {
+        get_optional_sized_utf8_string!(
+            self,
+            libfsntfs_file_entry_get_utf8_reparse_point_substitute_name_size,
+            libfsntfs_file_entry_get_utf8_reparse_point_substitute_name,
+            self.1
+        )
     }
 
-    pub fn get_security_descriptor_data(&self) {
-        unimplemented!();
+    /// Retrieves the raw self-relative security descriptor, if the entry has one.
+    pub fn get_security_descriptor_data(&self) -> Result<Option<Vec<u8>>, Error> {
+        let mut size = 0_usize;
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_get_security_descriptor_size(
+                self.as_type_ref(),
+                &mut size,
+                &mut error,
+            )
+        } {
+            1 => {}
+            0 => return Ok(None),
+            _ => return Err(self.1.ffi_error(error)),
+        }
+
+        let mut data = vec![0; size];
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_file_entry_get_security_descriptor(
+                self.as_type_ref(),
+                data.as_mut_ptr(),
+                data.len(),
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(Some(data))
+        }
     }
 
-    pub fn has_alternate_data_stream_by_name(&self, name: isize) {
-        unimplemented!();
+    /// Retrieves the parsed security descriptor, if the entry has one.
+    pub fn get_security_descriptor(&self) -> Result<Option<SecurityDescriptor>, Error> {
+        match self.get_security_descriptor_data()? {
+            Some(data) => Ok(Some(SecurityDescriptor::from_bytes(&data)?)),
+            None => Ok(None),
+        }
     }
 
-    pub fn has_default_data_stream(&self) {
-        unimplemented!();
+    /// Determines if the file entry has an alternate data stream with the given name.
+    pub fn has_alternate_data_stream_by_name(&self, name: &str) -> Result<bool, Error> {
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_has_alternate_data_stream_by_utf8_name(
+                self.as_type_ref(),
+                name.as_ptr(),
+                name.len(),
+                &mut error,
+            )
+        } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(self.1.ffi_error(error)),
+        }
     }
 
-    pub fn has_directory_entries_index(&self) {
-        unimplemented!();
+    /// Determines if the file entry has a default (unnamed) data stream.
+    pub fn has_default_data_stream(&self) -> Result<bool, Error> {
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_has_default_data_stream(self.as_type_ref(), &mut error)
+        } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(self.1.ffi_error(error)),
+        }
     }
 
-    pub fn is_empty(&self) {
-        unimplemented!();
+    /// Computes the digest `D` of the default data stream, without reading it into memory.
+    ///
+    /// Sparse extents are hashed as zeros without being read. The read offset of the entry is
+    /// left at the end of the data.
+    pub fn hash<D: Digest>(&self) -> Result<Vec<u8>, Error> {
+        let extents = self
+            .iter_extents()?
+            .collect::<Result<Vec<Extent>, Error>>()?;
+
+        digest_stream::<D, _>(self, self.get_size()?, &extents)
+    }
+
+    /// Determines if the file entry has a directory entries ($I30) index, i.e. is a directory.
+    pub fn has_directory_entries_index(&self) -> Result<bool, Error> {
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_file_entry_has_directory_entries_index(self.as_type_ref(), &mut error)
+        } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(self.1.ffi_error(error)),
+        }
+    }
+
+    /// Determines if the file entry is allocated (in use) in the MFT.
+    pub fn is_allocated(&self) -> Result<bool, Error> {
+        let mut error = ptr::null_mut();
+
+        match unsafe { libfsntfs_file_entry_is_allocated(self.as_type_ref(), &mut error) } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(self.1.ffi_error(error)),
+        }
+    }
+
+    /// Determines if the file entry is empty.
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        let mut error = ptr::null_mut();
+
+        match unsafe { libfsntfs_file_entry_is_empty(self.as_type_ref(), &mut error) } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(self.1.ffi_error(error)),
+        }
     }
 }
 
@@ -774,6 +1257,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_data_attribute() {
+        use crate::attribute::{AttributeType, AttributeWithInformation};
+
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let data_attribute = mft
+            .iter_attributes()
+            .unwrap()
+            .map(|a| a.unwrap())
+            .find(|a| a.get_type().unwrap() == AttributeType::Data)
+            .unwrap();
+
+        match data_attribute.get_data().unwrap() {
+            AttributeWithInformation::Data(data) => {
+                let (vcn_range_first, vcn_range_last) = data.vcn_range.unwrap();
+
+                assert_eq!(vcn_range_first, 0);
+                assert!(vcn_range_last >= vcn_range_first);
+                assert_eq!(data.size, mft.get_size().unwrap());
+            }
+            other => panic!("Expected a data attribute, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resident_data_attribute() {
+        use crate::attribute::{AttributeType, AttributeWithInformation};
+
+        let volume = sample_volume().unwrap();
+        // `$Volume` has an empty, resident $DATA attribute.
+        let volume_entry = volume.get_file_entry_by_mft_idx(3).unwrap();
+
+        let data_attribute = volume_entry
+            .iter_attributes()
+            .unwrap()
+            .map(|a| a.unwrap())
+            .find(|a| a.get_type().unwrap() == AttributeType::Data)
+            .unwrap();
+
+        match data_attribute.get_data().unwrap() {
+            AttributeWithInformation::Data(data) => {
+                assert_eq!(data.vcn_range, None);
+                assert_eq!(data.size, 0);
+            }
+            other => panic!("Expected a data attribute, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_standard_information_attribute() {
+        use crate::attribute::{AttributeType, AttributeWithInformation};
+
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let si_attribute = mft
+            .iter_attributes()
+            .unwrap()
+            .map(|a| a.unwrap())
+            .find(|a| a.get_type().unwrap() == AttributeType::StandardInformation)
+            .unwrap();
+
+        match si_attribute.get_data().unwrap() {
+            AttributeWithInformation::StandardInformation(si) => {
+                // Metadata files are protected by the well-known descriptor 256.
+                assert_eq!(si.security_descriptor_identifier, 256);
+                assert_eq!(
+                    si.file_attribute_flags,
+                    mft.get_file_attribute_flags().unwrap()
+                );
+            }
+            other => panic!("Expected a standard information attribute, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_read() {
         let volume = sample_volume().unwrap();
@@ -807,4 +1367,204 @@ mod tests {
 
         assert_eq!(buf.len(), 75776);
     }
+
+    #[test]
+    fn test_hash_matches_read_to_end() {
+        use sha2::{Digest, Sha256};
+
+        let volume = sample_volume().unwrap();
+        let mut entry = file_entry(&volume).unwrap();
+
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf).unwrap();
+
+        assert_eq!(
+            entry.hash::<Sha256>().unwrap(),
+            Sha256::digest(&buf).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_timestamps() {
+        use crate::utils::datetime_from_filetime;
+
+        let volume = sample_volume().unwrap();
+        let entry = file_entry(&volume).unwrap();
+
+        let creation_time = entry.get_creation_time().unwrap().unwrap();
+        let creation_time_as_integer = entry.get_creation_time_as_integer().unwrap();
+
+        assert_eq!(
+            creation_time,
+            datetime_from_filetime(creation_time_as_integer)
+        );
+        assert!(entry.get_modification_time().unwrap().is_some());
+        assert!(entry.get_access_time().unwrap().is_some());
+        assert!(entry.get_entry_modification_time().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_iter_alternate_data_streams() {
+        let volume = sample_volume().unwrap();
+        let secure = volume.get_file_entry_by_mft_idx(9).unwrap();
+
+        let names: Vec<String> = secure
+            .iter_alternate_data_streams()
+            .unwrap()
+            .map(|stream| stream.unwrap().get_name().unwrap())
+            .collect();
+
+        assert_eq!(
+            names.len(),
+            secure.get_number_of_alternate_data_streams().unwrap() as usize
+        );
+        assert!(names.contains(&"$SDS".to_string()));
+        assert!(secure.has_alternate_data_stream_by_name("$SDS").unwrap());
+        assert!(!secure
+            .has_alternate_data_stream_by_name("Zone.Identifier")
+            .unwrap());
+    }
+
+    #[test]
+    fn test_file_attribute_flags() {
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let flags = mft.get_file_attribute_flags().unwrap();
+
+        assert!(flags.contains(FileAttributeFlags::HIDDEN | FileAttributeFlags::SYSTEM));
+    }
+
+    #[test]
+    fn test_reparse_point_names_without_reparse_point() {
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        assert_eq!(mft.get_reparse_point_print_name().unwrap(), None);
+        assert_eq!(mft.get_reparse_point_substitute_name().unwrap(), None);
+    }
+
+    #[test]
+    fn test_security_descriptor() {
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let descriptor = mft.get_security_descriptor().unwrap().unwrap();
+
+        assert!(descriptor.owner.is_some());
+        assert!(descriptor.dacl.is_some());
+    }
+
+    #[test]
+    fn test_iter_extents() {
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let extents: Vec<Extent> = mft.iter_extents().unwrap().map(|e| e.unwrap()).collect();
+        let extents_size: u64 = extents.iter().map(|e| e.size).sum();
+
+        assert!(mft.is_allocated().unwrap());
+        assert!(!extents.is_empty());
+        assert!(extents
+            .iter()
+            .all(|e| !e.flags.contains(ExtentFlags::UNALLOCATED)));
+        assert!(extents_size >= mft.get_size().unwrap());
+    }
+
+    #[test]
+    fn test_names() {
+        let volume = sample_volume().unwrap();
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        let names = mft.names().unwrap();
+        assert!(!names.is_empty());
+        assert!(names.iter().any(|n| n.name == "$MFT"));
+        assert_eq!(mft.get_ntfs_name().unwrap(), "$MFT");
+        assert_eq!(
+            mft.get_name_utf16().unwrap(),
+            "$MFT".encode_utf16().collect::<Vec<u16>>()
+        );
+
+        let attribute_index = mft.get_name_attribute_index().unwrap();
+        assert_eq!(
+            mft.get_name_by_attribute_index(attribute_index).unwrap(),
+            mft.get_name().unwrap()
+        );
+        assert_eq!(
+            mft.get_parent_file_reference_by_attribute_index(attribute_index)
+                .unwrap(),
+            mft.get_parent_file_reference().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_sub_file_entry_by_name() {
+        let volume = sample_volume().unwrap();
+        let root = volume.get_root_directory().unwrap();
+
+        let extend = root.get_sub_file_entry_by_name("$EXTEND").unwrap().unwrap();
+        assert_eq!(extend.get_name().unwrap(), "$Extend");
+
+        assert!(root
+            .get_sub_file_entry_by_name("does-not-exist")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_find_by_name_folds_non_ascii_case() {
+        // Upcases ASCII and Latin-1 letters.
+        let mut data = Vec::new();
+        for c in 0..0x100_u16 {
+            let upper = match c {
+                0x61..=0x7a | 0xe0..=0xf6 | 0xf8..=0xfe => c - 0x20,
+                _ => c,
+            };
+            data.extend_from_slice(&upper.to_le_bytes());
+        }
+        let upcase_table = UpcaseTable::from_bytes(&data);
+
+        let candidates = || {
+            vec![
+                Ok((1, vec![NtfsName::from("menu.txt")])),
+                Ok((
+                    2,
+                    vec![NtfsName::from("CAFE~1.TXT"), NtfsName::from("Café.txt")],
+                )),
+            ]
+        };
+        let name: Vec<u16> = "CAFÉ.TXT".encode_utf16().collect();
+
+        assert_eq!(
+            find_by_name(candidates(), &upcase_table, &name).unwrap(),
+            Some(2)
+        );
+
+        let name: Vec<u16> = "caffè.txt".encode_utf16().collect();
+        assert_eq!(
+            find_by_name(candidates(), &upcase_table, &name).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_errors_after_abort_are_reported_as_aborted() {
+        let volume = sample_volume().unwrap();
+        let entry = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        volume.signal_abort().unwrap();
+
+        match entry.get_extent(-1) {
+            Err(Error::Aborted) => {}
+            other => panic!("expected `Error::Aborted`, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let volume = sample_volume().unwrap();
+        let entry = file_entry(&volume).unwrap();
+
+        assert!(!entry.is_empty().unwrap());
+    }
 }
@@ -0,0 +1,57 @@
+use std::fmt::{self, Display, Formatter};
+
+const ENTRY_INDEX_MASK: u64 = 0x0000_ffff_ffff_ffff;
+
+/// A reference to an MFT entry, as stored in $FILE_NAME attributes, indexes and the USN journal.
+///
+/// The lower 48 bits hold the MFT entry index, the upper 16 bits the sequence number
+/// the entry had when the reference was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileReference(pub u64);
+
+impl FileReference {
+    pub fn new(entry_index: u64, sequence_number: u16) -> Self {
+        FileReference((entry_index & ENTRY_INDEX_MASK) | u64::from(sequence_number) << 48)
+    }
+
+    pub fn entry_index(self) -> u64 {
+        self.0 & ENTRY_INDEX_MASK
+    }
+
+    pub fn sequence_number(self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+}
+
+impl From<u64> for FileReference {
+    fn from(reference: u64) -> Self {
+        FileReference(reference)
+    }
+}
+
+impl From<FileReference> for u64 {
+    fn from(reference: FileReference) -> Self {
+        reference.0
+    }
+}
+
+impl Display for FileReference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.entry_index(), self.sequence_number())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let reference = FileReference(0x0003_0000_0000_002a);
+
+        assert_eq!(reference.entry_index(), 42);
+        assert_eq!(reference.sequence_number(), 3);
+        assert_eq!(reference.to_string(), "42-3");
+        assert_eq!(FileReference::new(42, 3), reference);
+    }
+}
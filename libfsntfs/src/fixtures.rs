@@ -4,6 +4,8 @@ use crate::file_entry::FileEntry;
 use crate::volume::{AccessMode, Volume};
 use env_logger;
 use lazy_static::lazy_static;
+use std::fs::File;
+use std::io::Read;
 use std::path::PathBuf;
 use libbfio_rs::handle::{Handle, LibbfioAccessFlags};
 
@@ -28,6 +30,21 @@ pub fn sample_volume_path() -> String {
     sample.to_str().unwrap().to_string()
 }
 
+/// Offset of the sample volume in `sample_disk_image`, where the first partition of a disk
+/// image would start.
+pub const SAMPLE_PARTITION_OFFSET: u64 = 63 * 512;
+
+/// Returns a disk image which holds the sample volume at `SAMPLE_PARTITION_OFFSET`.
+pub fn sample_disk_image() -> Vec<u8> {
+    let mut image = vec![0; SAMPLE_PARTITION_OFFSET as usize];
+    File::open(sample_volume_path())
+        .unwrap()
+        .read_to_end(&mut image)
+        .unwrap();
+
+    image
+}
+
 pub fn sample_volume_io_handle() -> Result<Handle, Error> {
     let volume_path = sample_volume_path();
     Ok(Handle::open_file(volume_path, LibbfioAccessFlags::Read).expect("libbfio failed"))
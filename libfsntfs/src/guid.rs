@@ -0,0 +1,180 @@
+use chrono::prelude::*;
+use std::fmt::{self, Debug, Display, Formatter};
+use time::Duration;
+
+/// A GUID in its on-disk (Windows `GUID` structure) byte order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Guid([u8; 16]);
+
+/// The node identifier of a version 1 GUID, usually a MAC address of the generating host.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl Guid {
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Guid(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub fn is_nil(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    fn data1(&self) -> u32 {
+        u32::from(self.0[0])
+            | u32::from(self.0[1]) << 8
+            | u32::from(self.0[2]) << 16
+            | u32::from(self.0[3]) << 24
+    }
+
+    fn data2(&self) -> u16 {
+        u16::from(self.0[4]) | u16::from(self.0[5]) << 8
+    }
+
+    fn data3(&self) -> u16 {
+        u16::from(self.0[6]) | u16::from(self.0[7]) << 8
+    }
+
+    /// The version stored in the high nibble of `data3`, only meaningful for RFC 4122 GUIDs.
+    pub fn version(&self) -> u8 {
+        (self.data3() >> 12) as u8
+    }
+
+    /// Returns true if this GUID uses the RFC 4122 variant.
+    pub fn is_rfc4122(&self) -> bool {
+        self.0[8] & 0xc0 == 0x80
+    }
+
+    fn is_time_based(&self) -> bool {
+        self.is_rfc4122() && self.version() == 1
+    }
+
+    /// Retrieves the creation time of a version 1 (time based) GUID.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        if !self.is_time_based() {
+            return None;
+        }
+
+        // 100 nanosecond intervals since the start of the Gregorian calendar.
+        let ticks = u64::from(self.data3() & 0x0fff) << 48
+            | u64::from(self.data2()) << 32
+            | u64::from(self.data1());
+
+        Some(DateTime::from_utc(
+            NaiveDate::from_ymd(1582, 10, 15).and_hms(0, 0, 0)
+                + Duration::microseconds((ticks / 10) as i64),
+            Utc,
+        ))
+    }
+
+    /// Retrieves the clock sequence of a version 1 (time based) GUID.
+    pub fn clock_sequence(&self) -> Option<u16> {
+        if !self.is_time_based() {
+            return None;
+        }
+
+        Some(u16::from(self.0[8] & 0x3f) << 8 | u16::from(self.0[9]))
+    }
+
+    /// Retrieves the node identifier of a version 1 (time based) GUID.
+    pub fn mac_address(&self) -> Option<MacAddress> {
+        if !self.is_time_based() {
+            return None;
+        }
+
+        let mut node = [0; 6];
+        node.copy_from_slice(&self.0[10..]);
+
+        Some(MacAddress(node))
+    }
+}
+
+impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-",
+            self.data1(),
+            self.data2(),
+            self.data3(),
+            self.0[8],
+            self.0[9]
+        )?;
+
+        for b in &self.0[10..] {
+            write!(f, "{:02x}", b)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for Guid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for MacAddress {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let m = &self.0;
+
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            m[0], m[1], m[2], m[3], m[4], m[5]
+        )
+    }
+}
+
+impl Debug for MacAddress {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // c232ab00-9414-11ec-b3c8-9f6bdeced846, the version 1 example from RFC 9562.
+    const V1_GUID: [u8; 16] = [
+        0x00, 0xab, 0x32, 0xc2, 0x14, 0x94, 0xec, 0x11, 0xb3, 0xc8, 0x9f, 0x6b, 0xde, 0xce, 0xd8,
+        0x46,
+    ];
+
+    #[test]
+    fn test_display() {
+        let guid = Guid::from_bytes(V1_GUID);
+
+        assert_eq!(guid.to_string(), "c232ab00-9414-11ec-b3c8-9f6bdeced846");
+    }
+
+    #[test]
+    fn test_v1_fields() {
+        let guid = Guid::from_bytes(V1_GUID);
+
+        assert_eq!(guid.version(), 1);
+        assert_eq!(
+            guid.timestamp(),
+            Some(Utc.ymd(2022, 2, 22).and_hms(19, 22, 22))
+        );
+        assert_eq!(guid.clock_sequence(), Some(0x33c8));
+        assert_eq!(guid.mac_address().unwrap().to_string(), "9f:6b:de:ce:d8:46");
+    }
+
+    #[test]
+    fn test_v4_has_no_timestamp() {
+        let mut bytes = V1_GUID;
+        bytes[7] = 0x41;
+
+        let guid = Guid::from_bytes(bytes);
+
+        assert_eq!(guid.version(), 4);
+        assert!(guid.timestamp().is_none());
+        assert!(guid.mac_address().is_none());
+    }
+}
@@ -0,0 +1,450 @@
+//! Streaming hashes of data streams, see `FileEntry::hash` and `Volume::hash_all`.
+use crate::error::Error;
+use crate::extent::{Extent, ExtentFlags};
+use digest::Digest;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::Sha256;
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Fed to the digests in place of the contents of sparse extents.
+static ZEROS: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+
+/// Feeds the `size` bytes of a data stream to `update`, one buffer at a time.
+///
+/// The stream is read extent by extent, and sparse extents are fed as zeros without reading
+/// them. Streams which are resident or compressed are read as a whole, as their extents do
+/// not map directly to their contents.
+pub(crate) fn hash_stream<R, F>(
+    mut reader: R,
+    size: u64,
+    extents: &[Extent],
+    mut update: F,
+) -> Result<(), Error>
+where
+    R: Read + Seek,
+    F: FnMut(&[u8]),
+{
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    if extents.is_empty()
+        || extents
+            .iter()
+            .any(|extent| extent.flags.contains(ExtentFlags::COMPRESSED))
+    {
+        reader.seek(SeekFrom::Start(0)).map_err(Error::Io)?;
+
+        return copy(&mut reader, size, &mut buffer, &mut update);
+    }
+
+    let mut position = 0;
+
+    for extent in extents {
+        if position >= size {
+            break;
+        }
+
+        // The last extent usually extends past the end of the data, up to the next cluster.
+        let length = extent.size.min(size - position);
+
+        if extent.flags.contains(ExtentFlags::SPARSE) {
+            let mut remaining = length;
+
+            while remaining > 0 {
+                let chunk = remaining.min(BUFFER_SIZE as u64) as usize;
+                update(&ZEROS[..chunk]);
+                remaining -= chunk as u64;
+            }
+        } else {
+            reader.seek(SeekFrom::Start(position)).map_err(Error::Io)?;
+            copy(&mut reader, length, &mut buffer, &mut update)?;
+        }
+
+        position += length;
+    }
+
+    if position < size {
+        reader.seek(SeekFrom::Start(position)).map_err(Error::Io)?;
+        copy(&mut reader, size - position, &mut buffer, &mut update)?;
+    }
+
+    Ok(())
+}
+
+fn copy<R, F>(reader: &mut R, length: u64, buffer: &mut [u8], update: &mut F) -> Result<(), Error>
+where
+    R: Read,
+    F: FnMut(&[u8]),
+{
+    let mut remaining = length;
+
+    while remaining > 0 {
+        let chunk = remaining.min(buffer.len() as u64) as usize;
+
+        reader.read_exact(&mut buffer[..chunk]).map_err(Error::Io)?;
+        update(&buffer[..chunk]);
+
+        remaining -= chunk as u64;
+    }
+
+    Ok(())
+}
+
+/// Computes a single digest of a data stream.
+pub(crate) fn digest_stream<D, R>(
+    reader: R,
+    size: u64,
+    extents: &[Extent],
+) -> Result<Vec<u8>, Error>
+where
+    D: Digest,
+    R: Read + Seek,
+{
+    let mut digest = D::new();
+
+    hash_stream(reader, size, extents, |data| digest.input(data))?;
+
+    Ok(digest.result().to_vec())
+}
+
+/// The MD5, SHA-1 and SHA-256 digests of a data stream, computed in one pass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Digests {
+    pub md5: [u8; 16],
+    pub sha1: [u8; 20],
+    pub sha256: [u8; 32],
+}
+
+impl Digests {
+    fn compute<R: Read + Seek>(reader: R, size: u64, extents: &[Extent]) -> Result<Self, Error> {
+        let mut md5 = Md5::new();
+        let mut sha1 = Sha1::new();
+        let mut sha256 = Sha256::new();
+
+        hash_stream(reader, size, extents, |data| {
+            md5.input(data);
+            sha1.input(data);
+            sha256.input(data);
+        })?;
+
+        let mut digests = Digests {
+            md5: [0; 16],
+            sha1: [0; 20],
+            sha256: [0; 32],
+        };
+
+        digests.md5.copy_from_slice(&md5.result());
+        digests.sha1.copy_from_slice(&sha1.result());
+        digests.sha256.copy_from_slice(&sha256.result());
+
+        Ok(digests)
+    }
+}
+
+/// A data stream listed in a `HashManifest`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamHashes {
+    /// Path of the file entry, as yielded by `Volume::walk`.
+    pub path: String,
+    /// Name of the alternate data stream, escaped with `NtfsName::escape`.
+    /// `None` for the default data stream.
+    pub stream_name: Option<String>,
+    pub size: u64,
+    pub digests: Digests,
+}
+
+impl StreamHashes {
+    pub(crate) fn compute<R: Read + Seek>(
+        path: String,
+        stream_name: Option<String>,
+        reader: R,
+        size: u64,
+        extents: &[Extent],
+    ) -> Result<Self, Error> {
+        let digests = Digests::compute(reader, size, extents)?;
+
+        Ok(StreamHashes {
+            path,
+            stream_name,
+            size,
+            digests,
+        })
+    }
+
+    /// Returns the path of the stream, `path:stream_name` for alternate data streams.
+    pub fn stream_path(&self) -> String {
+        match self.stream_name {
+            Some(ref stream_name) => format!("{}:{}", self.path, stream_name),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// A file entry or data stream which could not be hashed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashError {
+    /// Path of the file entry, `None` if the error occurred before its path was known.
+    pub path: Option<String>,
+    /// Name of the alternate data stream, if the error is specific to one.
+    pub stream_name: Option<String>,
+    pub message: String,
+}
+
+impl HashError {
+    /// Returns the path of the file entry or stream which failed, `-` if it is not known.
+    pub fn stream_path(&self) -> String {
+        match (&self.path, &self.stream_name) {
+            (Some(path), Some(stream_name)) => format!("{}:{}", path, stream_name),
+            (Some(path), None) => path.clone(),
+            (None, _) => "-".to_owned(),
+        }
+    }
+}
+
+/// The hashes of every data stream of a volume, created by `Volume::hash_all`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashManifest {
+    pub streams: Vec<StreamHashes>,
+    /// The file entries and data streams which are missing from `streams`.
+    pub errors: Vec<HashError>,
+}
+
+impl HashManifest {
+    /// Records that a file entry or data stream could not be hashed.
+    ///
+    /// `Error::Aborted` is returned instead, as nothing else can be hashed after it.
+    pub(crate) fn record_error(
+        &mut self,
+        path: Option<&str>,
+        stream_name: Option<&str>,
+        error: Error,
+    ) -> Result<(), Error> {
+        if let Error::Aborted = error {
+            return Err(error);
+        }
+
+        self.errors.push(HashError {
+            path: path.map(str::to_owned),
+            stream_name: stream_name.map(str::to_owned),
+            message: error.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Writes the manifest as tab separated values, one line per data stream:
+    /// MD5, SHA-1, SHA-256, size and path, as hexadecimal digests.
+    ///
+    /// If any errors were recorded, they follow in a second table, after an empty line, with
+    /// one line per error: path and message. Names in paths are escaped, and messages are
+    /// escaped like `str::escape_debug`, so neither contains tabs or line breaks.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "md5\tsha1\tsha256\tsize\tpath")?;
+
+        for stream in &self.streams {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                to_hex(&stream.digests.md5),
+                to_hex(&stream.digests.sha1),
+                to_hex(&stream.digests.sha256),
+                stream.size,
+                stream.stream_path()
+            )?;
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "path\terror")?;
+
+            for error in &self.errors {
+                writeln!(
+                    writer,
+                    "{}\t{}",
+                    error.stream_path(),
+                    error.message.escape_debug()
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+
+    for b in bytes {
+        write!(hex, "{:02x}", b).expect("writing to a String cannot fail");
+    }
+
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::NtfsName;
+    use std::io::Cursor;
+
+    fn extent(size: u64, flags: ExtentFlags) -> Extent {
+        Extent {
+            offset: 0,
+            size,
+            flags,
+        }
+    }
+
+    fn collect(data: &[u8], size: u64, extents: &[Extent]) -> Vec<u8> {
+        let mut fed = Vec::new();
+
+        hash_stream(Cursor::new(data), size, extents, |chunk| {
+            fed.extend_from_slice(chunk)
+        })
+        .unwrap();
+
+        fed
+    }
+
+    #[test]
+    fn test_resident_stream_is_read_as_a_whole() {
+        assert_eq!(collect(b"resident data", 13, &[]), b"resident data");
+    }
+
+    #[test]
+    fn test_sparse_extents_are_fed_as_zeros() {
+        // A reader which would return 0xff for the sparse range, had it been read.
+        let data = [1, 1, 0xff, 0xff, 0xff, 2];
+        let extents = [
+            extent(2, ExtentFlags::empty()),
+            extent(3, ExtentFlags::SPARSE),
+            extent(4096, ExtentFlags::empty()),
+        ];
+
+        assert_eq!(collect(&data, 6, &extents), [1, 1, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_compressed_stream_is_read_as_a_whole() {
+        let data = [1, 2, 3, 4];
+        let extents = [
+            extent(2, ExtentFlags::COMPRESSED),
+            extent(2, ExtentFlags::SPARSE),
+        ];
+
+        assert_eq!(collect(&data, 4, &extents), data);
+    }
+
+    #[test]
+    fn test_short_stream_is_an_error() {
+        let result = hash_stream(Cursor::new([0_u8; 2]), 4, &[], |_| {});
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stream_hashes_of_empty_stream() {
+        let hashes =
+            StreamHashes::compute("/empty".to_owned(), None, Cursor::new([]), 0, &[]).unwrap();
+        let digests = hashes.digests;
+
+        assert_eq!(to_hex(&digests.md5), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            to_hex(&digests.sha1),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            to_hex(&digests.sha256),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_sparse_stream_is_hashed_as_zeros() {
+        let size = 3 * BUFFER_SIZE + 5;
+        // An empty reader, which fails if the stream is read at all.
+        let extents = [extent(1 << 20, ExtentFlags::SPARSE)];
+        let hashes = StreamHashes::compute(
+            "/sparse".to_owned(),
+            None,
+            Cursor::new([]),
+            size as u64,
+            &extents,
+        )
+        .unwrap();
+
+        let zeros = vec![0; size];
+
+        assert_eq!(&hashes.digests.md5[..], &Md5::digest(&zeros)[..]);
+        assert_eq!(&hashes.digests.sha1[..], &Sha1::digest(&zeros)[..]);
+        assert_eq!(&hashes.digests.sha256[..], &Sha256::digest(&zeros)[..]);
+    }
+
+    #[test]
+    fn test_record_error() {
+        let mut manifest = HashManifest::default();
+
+        manifest
+            .record_error(Some("/a"), None, Error::Other("failed".to_owned()))
+            .unwrap();
+        assert_eq!(manifest.errors[0].path, Some("/a".to_owned()));
+
+        assert!(manifest
+            .record_error(Some("/b"), None, Error::Aborted)
+            .is_err());
+        assert_eq!(manifest.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_write_manifest() {
+        let hashes = StreamHashes::compute(
+            format!("/{}", NtfsName::from("a\tb").escape()),
+            Some("ads".to_owned()),
+            Cursor::new(b"abc"),
+            3,
+            &[],
+        )
+        .unwrap();
+        let errors = vec![
+            HashError {
+                path: Some("/b".to_owned()),
+                stream_name: Some("ads".to_owned()),
+                message: "failed\nto read".to_owned(),
+            },
+            HashError {
+                path: None,
+                stream_name: None,
+                message: "failed".to_owned(),
+            },
+        ];
+
+        let mut output = Vec::new();
+        HashManifest {
+            streams: vec![hashes],
+            errors,
+        }
+        .write_to(&mut output)
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[1].starts_with("900150983cd24fb0d6963f7d28e17f72\t"));
+        assert!(lines[1].ends_with("\t3\t/a\\u{0009}b:ads"));
+        assert_eq!(lines[2], "");
+        assert_eq!(lines[3], "path\terror");
+        assert_eq!(lines[4], "/b:ads\tfailed\\nto read");
+        assert_eq!(lines[5], "-\tfailed");
+    }
+
+    #[test]
+    fn test_write_manifest_without_errors() {
+        let mut output = Vec::new();
+        HashManifest::default().write_to(&mut output).unwrap();
+
+        assert_eq!(output, b"md5\tsha1\tsha256\tsize\tpath\n");
+    }
+}
@@ -6,11 +6,24 @@
 extern crate libyal_rs_common;
 
 pub mod attribute;
+pub mod data_stream;
 pub mod error;
+pub mod extent;
 pub mod ffi_error;
 pub mod file_entry;
+pub mod file_reference;
+pub mod guid;
+pub mod hash;
+pub mod name;
+pub mod owned;
+pub mod reparse_tag;
+pub mod security_descriptor;
+pub mod shared_volume;
+mod upcase;
+pub mod usn_change_journal;
 mod utils;
 pub mod volume;
+pub mod walk;
 
 #[cfg(test)]
 mod fixtures;
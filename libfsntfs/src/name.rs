@@ -0,0 +1,180 @@
+use std::char::decode_utf16;
+use std::ffi::OsString;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// A name as stored on NTFS: a sequence of UTF-16 code units which need not be valid UTF-16.
+///
+/// NTFS does not validate names, so they may contain unpaired surrogates which cannot be
+/// represented as a `String`. This type keeps the name as is, and offers lossless
+/// (`to_os_string`), lossy (`Display`) and escaped conversions.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct NtfsName(Vec<u16>);
+
+impl NtfsName {
+    pub fn from_utf16(name: Vec<u16>) -> Self {
+        NtfsName(name)
+    }
+
+    pub fn as_utf16(&self) -> &[u16] {
+        &self.0
+    }
+
+    pub fn into_utf16(self) -> Vec<u16> {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the name as a `String`, or `None` if it is not valid UTF-16.
+    pub fn to_utf8(&self) -> Option<String> {
+        String::from_utf16(&self.0).ok()
+    }
+
+    /// Converts the name, replacing unpaired surrogates with U+FFFD.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(&self.0)
+    }
+
+    /// Converts the name without loss.
+    ///
+    /// On Windows this is the native representation. On Unix the name is encoded as WTF-8,
+    /// which is UTF-8 except that unpaired surrogates are encoded like any other code point.
+    pub fn to_os_string(&self) -> OsString {
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStringExt;
+
+            OsString::from_wide(&self.0)
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+
+            OsString::from_vec(self.to_wtf8())
+        }
+
+        #[cfg(not(any(windows, unix)))]
+        {
+            OsString::from(self.to_string_lossy())
+        }
+    }
+
+    fn to_wtf8(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.0.len());
+
+        for c in decode_utf16(self.0.iter().cloned()) {
+            match c {
+                Ok(c) => {
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                Err(e) => {
+                    let surrogate = e.unpaired_surrogate();
+
+                    bytes.push(0xe0 | (surrogate >> 12) as u8);
+                    bytes.push(0x80 | ((surrogate >> 6) & 0x3f) as u8);
+                    bytes.push(0x80 | (surrogate & 0x3f) as u8);
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Converts the name to a printable `String` which can be mapped back to the original.
+    ///
+    /// Unpaired surrogates and control characters are written as `\u{XXXX}`,
+    /// and backslashes are doubled.
+    pub fn escape(&self) -> String {
+        let mut escaped = String::with_capacity(self.0.len());
+
+        for c in decode_utf16(self.0.iter().cloned()) {
+            match c {
+                Ok('\\') => escaped.push_str("\\\\"),
+                Ok(c) if c.is_control() => escaped.push_str(&format!("\\u{{{:04x}}}", c as u32)),
+                Ok(c) => escaped.push(c),
+                Err(e) => escaped.push_str(&format!("\\u{{{:04x}}}", e.unpaired_surrogate())),
+            }
+        }
+
+        escaped
+    }
+}
+
+impl From<&str> for NtfsName {
+    fn from(name: &str) -> Self {
+        NtfsName(name.encode_utf16().collect())
+    }
+}
+
+impl PartialEq<str> for NtfsName {
+    fn eq(&self, other: &str) -> bool {
+        self.0.iter().cloned().eq(other.encode_utf16())
+    }
+}
+
+impl<'a> PartialEq<&'a str> for NtfsName {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl Display for NtfsName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+impl Debug for NtfsName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.escape())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "a", an unpaired high surrogate, "b".
+    fn unpaired() -> NtfsName {
+        NtfsName::from_utf16(vec![0x61, 0xd800, 0x62])
+    }
+
+    #[test]
+    fn test_valid_name() {
+        let name = NtfsName::from("$MFT");
+
+        assert_eq!(name.to_utf8(), Some("$MFT".to_owned()));
+        assert_eq!(name, "$MFT");
+        assert_eq!(name.escape(), "$MFT");
+    }
+
+    #[test]
+    fn test_unpaired_surrogate() {
+        let name = unpaired();
+
+        assert_eq!(name.to_utf8(), None);
+        assert_eq!(name.to_string(), "a\u{fffd}b");
+        assert_eq!(name.escape(), "a\\u{d800}b");
+    }
+
+    #[test]
+    fn test_escape_is_unambiguous() {
+        let name = NtfsName::from("a\\u{d800}\tb");
+
+        assert_eq!(name.escape(), "a\\\\u{d800}\\u{0009}b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_to_os_string_is_wtf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let os_string = unpaired().to_os_string();
+
+        assert_eq!(os_string.as_bytes(), &[0x61, 0xed, 0xa0, 0x80, 0x62]);
+    }
+}
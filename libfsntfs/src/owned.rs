@@ -0,0 +1,317 @@
+//! File entries and attributes which own the volume they were opened from.
+//!
+//! `FileEntry<'a>` borrows its `Volume`, and `Attribute<'a>` borrows its `FileEntry`, which
+//! ties them to the scope of the volume. `OwnedFileEntry` instead takes a volume of its own
+//! from a `SharedVolume`, and returns it to the pool when dropped, and `OwnedAttribute` keeps
+//! its `OwnedFileEntry` alive, so they can be stored in long-lived structs and returned from
+//! functions. File entries can also be sent to other threads.
+use crate::attribute::Attribute;
+use crate::error::Error;
+use crate::file_entry::FileEntry;
+use crate::file_reference::FileReference;
+use crate::shared_volume::{OwnedVolume, SharedVolume};
+use crate::volume::{MftEntryIndex, Volume};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::mem;
+use std::os::raw::c_int;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A file entry which owns its volume.
+///
+/// Every `OwnedFileEntry` takes a volume of its own from the `SharedVolume` it was opened
+/// from, so opening many of them at once opens as many volumes.
+pub struct OwnedFileEntry {
+    // Declared before `volume`, so the entry is freed before the volume is returned.
+    entry: FileEntry<'static>,
+    volume: OwnedVolume,
+}
+
+// The volume, and the libfsntfs file entry which is the only object borrowing it, are owned
+// by this handle alone, so they are always moved to another thread together and never used
+// from two threads at once. See `PooledVolume` for why the volume itself may be moved.
+unsafe impl Send for OwnedFileEntry {}
+
+impl OwnedFileEntry {
+    /// Opens a file entry of a volume taken from `shared` with `open`, such as
+    /// `OwnedFileEntry::new(shared, |v| v.get_root_directory())`.
+    ///
+    /// `open` must return a file entry of the volume it is given, which is why this is not
+    /// public.
+    pub(crate) fn new<F>(shared: Arc<SharedVolume>, open: F) -> Result<Self, Error>
+    where
+        F: for<'v> FnOnce(&'v Volume) -> Result<FileEntry<'v>, Error>,
+    {
+        OwnedFileEntry::try_new(shared, |volume| open(volume).map(Some))
+            .map(|entry| entry.expect("`open` always returns a file entry"))
+    }
+
+    /// Like `new`, for lookups which may not find a file entry.
+    fn try_new<F>(shared: Arc<SharedVolume>, open: F) -> Result<Option<Self>, Error>
+    where
+        F: for<'v> FnOnce(&'v Volume) -> Result<Option<FileEntry<'v>>, Error>,
+    {
+        let volume = OwnedVolume::take(shared)?;
+        let entry = match open(&volume)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        // The entry only borrows the volume, which is boxed (so it does not move) and is
+        // kept alive by the `OwnedVolume` stored next to it.
+        let entry = unsafe { mem::transmute::<FileEntry, FileEntry<'static>>(entry) };
+
+        Ok(Some(OwnedFileEntry { entry, volume }))
+    }
+
+    /// Retrieves a specific file entry of `shared`.
+    pub fn from_mft_idx(shared: Arc<SharedVolume>, idx: MftEntryIndex) -> Result<Self, Error> {
+        OwnedFileEntry::new(shared, |volume| volume.get_file_entry_by_mft_idx(idx))
+    }
+
+    /// Retrieves the file entry a file reference points to, see
+    /// `Volume::get_file_entry_by_reference`.
+    pub fn from_file_reference(
+        shared: Arc<SharedVolume>,
+        file_reference: FileReference,
+    ) -> Result<Option<Self>, Error> {
+        OwnedFileEntry::try_new(shared, |volume| {
+            volume.get_file_entry_by_reference(file_reference)
+        })
+    }
+
+    /// Retrieves the file entry at `path`, see `Volume::get_file_entry_by_path`.
+    pub fn from_path(
+        shared: Arc<SharedVolume>,
+        path: impl AsRef<Path>,
+    ) -> Result<Option<Self>, Error> {
+        OwnedFileEntry::try_new(shared, |volume| volume.get_file_entry_by_path(path))
+    }
+
+    /// Retrieves the root directory of `shared`.
+    pub fn root_directory(shared: Arc<SharedVolume>) -> Result<Self, Error> {
+        OwnedFileEntry::new(shared, |volume| volume.get_root_directory())
+    }
+
+    /// Returns the file entry, borrowed for no longer than `self`.
+    pub fn entry(&self) -> &FileEntry {
+        &self.entry
+    }
+
+    /// Returns the volume the file entry was opened from.
+    pub fn volume(&self) -> &Volume {
+        &self.volume
+    }
+
+    /// Returns the shared volume the file entry was opened from.
+    pub fn shared_volume(&self) -> &Arc<SharedVolume> {
+        self.volume.shared()
+    }
+
+    /// Opens the same file entry again, from another volume of the pool.
+    ///
+    /// The new entry has a read offset of its own.
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        let idx = self.entry.get_file_reference()?.entry_index();
+
+        OwnedFileEntry::from_mft_idx(self.shared_volume().clone(), idx)
+    }
+
+    /// Retrieves the sub file entry at `sub_file_entry_index`, from another volume of the pool.
+    pub fn get_sub_file_entry(&self, sub_file_entry_index: c_int) -> Result<Self, Error> {
+        let idx = self.entry.get_file_reference()?.entry_index();
+
+        OwnedFileEntry::new(self.shared_volume().clone(), |volume| {
+            volume
+                .get_file_entry_by_mft_idx(idx)?
+                .get_sub_file_entry(sub_file_entry_index)
+        })
+    }
+
+    /// Retrieves the attribute at `attribute_index`, which shares the file entry (and its
+    /// volume) with `self`.
+    pub fn get_attribute_by_index(
+        self: &Arc<Self>,
+        attribute_index: c_int,
+    ) -> Result<OwnedAttribute, Error> {
+        OwnedAttribute::new(self.clone(), attribute_index)
+    }
+
+    /// Retrieves all the attributes of the file entry.
+    pub fn attributes(self: &Arc<Self>) -> Result<Vec<OwnedAttribute>, Error> {
+        (0..self.entry.get_number_of_attributes()?)
+            .map(|idx| self.get_attribute_by_index(idx))
+            .collect()
+    }
+}
+
+impl Read for OwnedFileEntry {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        (&self.entry).read(buf)
+    }
+}
+
+impl Seek for OwnedFileEntry {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
+        (&self.entry).seek(pos)
+    }
+}
+
+/// An attribute which keeps its file entry, and therefore its volume, alive.
+///
+/// The attributes of a file entry share it, and so use the same volume. For that reason
+/// they are not `Send` (`Arc<OwnedFileEntry>` is not, as the entry is not `Sync`): send the
+/// `OwnedFileEntry` instead, and retrieve its attributes on the receiving thread.
+pub struct OwnedAttribute {
+    // Declared before `file_entry`, so the attribute is freed before the entry.
+    attribute: Attribute<'static>,
+    file_entry: Arc<OwnedFileEntry>,
+}
+
+impl OwnedAttribute {
+    fn new(file_entry: Arc<OwnedFileEntry>, attribute_index: c_int) -> Result<Self, Error> {
+        let attribute = file_entry.entry.get_attribute_by_index(attribute_index)?;
+
+        // The attribute borrows the entry, which is behind an `Arc` (so it does not move) and
+        // is kept alive by the reference stored next to it.
+        let attribute = unsafe { mem::transmute::<Attribute, Attribute<'static>>(attribute) };
+
+        Ok(OwnedAttribute {
+            attribute,
+            file_entry,
+        })
+    }
+
+    /// Returns the attribute, borrowed for no longer than `self`.
+    pub fn attribute(&self) -> &Attribute {
+        &self.attribute
+    }
+
+    /// Returns the file entry of the attribute.
+    pub fn file_entry(&self) -> &Arc<OwnedFileEntry> {
+        &self.file_entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::AttributeType;
+    use crate::fixtures::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    fn shared_volume() -> Arc<SharedVolume> {
+        Arc::new(SharedVolume::open(sample_volume_path()).unwrap())
+    }
+
+    fn open_mft() -> OwnedFileEntry {
+        OwnedFileEntry::from_mft_idx(shared_volume(), 0).unwrap()
+    }
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn test_owned_file_entry_is_send() {
+        assert_send::<OwnedFileEntry>();
+    }
+
+    #[test]
+    fn test_owned_file_entry_outlives_scope_of_volume() {
+        let mut mft = open_mft();
+
+        assert_eq!(mft.entry().get_name().unwrap(), "$MFT");
+
+        let mut signature = [0; 4];
+        mft.read_exact(&mut signature).unwrap();
+        assert_eq!(&signature, b"FILE");
+    }
+
+    #[test]
+    fn test_owned_attribute_outlives_its_file_entry() {
+        let attributes = Arc::new(open_mft()).attributes().unwrap();
+
+        assert!(attributes
+            .iter()
+            .any(|a| a.attribute().get_type().unwrap() == AttributeType::FileName));
+    }
+
+    #[test]
+    fn test_owned_attributes_share_their_file_entry() {
+        let shared = shared_volume();
+
+        let mft = Arc::new(OwnedFileEntry::from_mft_idx(shared.clone(), 0).unwrap());
+        let attributes = mft.attributes().unwrap();
+        drop(mft);
+
+        assert!(attributes.len() > 1);
+        assert_eq!(shared.number_of_idle_volumes(), 0);
+
+        drop(attributes);
+        assert_eq!(shared.number_of_idle_volumes(), 1);
+    }
+
+    #[test]
+    fn test_owned_file_entry_lookups() {
+        let shared = shared_volume();
+
+        let mft = OwnedFileEntry::from_path(shared.clone(), "\\$MFT")
+            .unwrap()
+            .unwrap();
+        let reference = mft.entry().get_file_reference().unwrap();
+
+        let by_reference = OwnedFileEntry::from_file_reference(shared.clone(), reference)
+            .unwrap()
+            .unwrap();
+        assert_eq!(by_reference.entry().get_name().unwrap(), "$MFT");
+
+        assert!(OwnedFileEntry::from_path(shared, "\\does-not-exist")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_owned_sub_file_entries() {
+        let root = OwnedFileEntry::root_directory(shared_volume()).unwrap();
+
+        let sub_entries: Vec<OwnedFileEntry> =
+            (0..root.entry().get_number_of_sub_file_entries().unwrap())
+                .map(|idx| root.get_sub_file_entry(idx).unwrap())
+                .collect();
+        drop(root);
+
+        assert!(sub_entries
+            .iter()
+            .any(|e| e.entry().get_name().unwrap() == "$MFT"));
+    }
+
+    #[test]
+    fn test_volumes_are_returned_to_the_pool() {
+        let shared = shared_volume();
+
+        let mft = OwnedFileEntry::from_mft_idx(shared.clone(), 0).unwrap();
+        let copy = mft.try_clone().unwrap();
+        assert_eq!(shared.number_of_idle_volumes(), 0);
+
+        drop(mft);
+        drop(copy);
+        assert_eq!(shared.number_of_idle_volumes(), 2);
+    }
+
+    #[test]
+    fn test_send_owned_file_entry_over_channel() {
+        let (sender, receiver) = mpsc::channel();
+
+        let worker = thread::spawn(move || {
+            let mut mft: OwnedFileEntry = receiver.recv().unwrap();
+
+            let mut signature = [0; 4];
+            mft.read_exact(&mut signature).unwrap();
+            signature
+        });
+
+        sender.send(open_mft()).unwrap();
+
+        assert_eq!(&worker.join().unwrap(), b"FILE");
+    }
+}
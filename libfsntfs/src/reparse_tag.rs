@@ -0,0 +1,165 @@
+/// Set on tags owned by Microsoft.
+const MICROSOFT_BIT: u32 = 0x8000_0000;
+/// Set on tags which point to another named entity (symbolic links and junctions).
+const NAME_SURROGATE_BIT: u32 = 0x2000_0000;
+/// Set on tags which may be applied to non-empty directories.
+const DIRECTORY_BIT: u32 = 0x1000_0000;
+
+/// The cloud files tags differ only in bits 12-15 (`IO_REPARSE_TAG_CLOUD_*`).
+const CLOUD_TAG: u32 = 0x9000_001A;
+const CLOUD_MASK: u32 = 0xFFFF_0FFF;
+
+/// The reparse tag of a reparse point (`IO_REPARSE_TAG_*`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReparseTag {
+    MountPoint,
+    Hsm,
+    Hsm2,
+    Sis,
+    Wim,
+    Csv,
+    Dfs,
+    Symlink,
+    Dfsr,
+    Dedup,
+    Nfs,
+    FilePlaceholder,
+    Wof,
+    Wci,
+    GlobalReparse,
+    /// Cloud files (OneDrive), carrying the sub-type stored in bits 12-15 of the tag.
+    Cloud(u8),
+    AppExecLink,
+    Projfs,
+    LxSymlink,
+    StorageSync,
+    WciTombstone,
+    Unhandled,
+    OneDrive,
+    ProjfsTombstone,
+    AfUnix,
+    LxFifo,
+    LxChr,
+    LxBlk,
+    Unknown(u32),
+}
+
+impl From<u32> for ReparseTag {
+    fn from(tag: u32) -> Self {
+        match tag {
+            0xA000_0003 => ReparseTag::MountPoint,
+            0xC000_0004 => ReparseTag::Hsm,
+            0x8000_0006 => ReparseTag::Hsm2,
+            0x8000_0007 => ReparseTag::Sis,
+            0x8000_0008 => ReparseTag::Wim,
+            0x8000_0009 => ReparseTag::Csv,
+            0x8000_000A => ReparseTag::Dfs,
+            0xA000_000C => ReparseTag::Symlink,
+            0x8000_0012 => ReparseTag::Dfsr,
+            0x8000_0013 => ReparseTag::Dedup,
+            0x8000_0014 => ReparseTag::Nfs,
+            0x8000_0015 => ReparseTag::FilePlaceholder,
+            0x8000_0017 => ReparseTag::Wof,
+            0x8000_0018 => ReparseTag::Wci,
+            0xA000_0019 => ReparseTag::GlobalReparse,
+            0x8000_001B => ReparseTag::AppExecLink,
+            0x9000_001C => ReparseTag::Projfs,
+            0xA000_001D => ReparseTag::LxSymlink,
+            0x8000_001E => ReparseTag::StorageSync,
+            0xA000_001F => ReparseTag::WciTombstone,
+            0x8000_0020 => ReparseTag::Unhandled,
+            0x8000_0021 => ReparseTag::OneDrive,
+            0xA000_0022 => ReparseTag::ProjfsTombstone,
+            0x8000_0023 => ReparseTag::AfUnix,
+            0x8000_0024 => ReparseTag::LxFifo,
+            0x8000_0025 => ReparseTag::LxChr,
+            0x8000_0026 => ReparseTag::LxBlk,
+            tag if tag & CLOUD_MASK == CLOUD_TAG => ReparseTag::Cloud(((tag >> 12) & 0xf) as u8),
+            tag => ReparseTag::Unknown(tag),
+        }
+    }
+}
+
+impl ReparseTag {
+    /// The raw value of the tag.
+    pub fn value(self) -> u32 {
+        match self {
+            ReparseTag::MountPoint => 0xA000_0003,
+            ReparseTag::Hsm => 0xC000_0004,
+            ReparseTag::Hsm2 => 0x8000_0006,
+            ReparseTag::Sis => 0x8000_0007,
+            ReparseTag::Wim => 0x8000_0008,
+            ReparseTag::Csv => 0x8000_0009,
+            ReparseTag::Dfs => 0x8000_000A,
+            ReparseTag::Symlink => 0xA000_000C,
+            ReparseTag::Dfsr => 0x8000_0012,
+            ReparseTag::Dedup => 0x8000_0013,
+            ReparseTag::Nfs => 0x8000_0014,
+            ReparseTag::FilePlaceholder => 0x8000_0015,
+            ReparseTag::Wof => 0x8000_0017,
+            ReparseTag::Wci => 0x8000_0018,
+            ReparseTag::GlobalReparse => 0xA000_0019,
+            ReparseTag::Cloud(sub_type) => CLOUD_TAG | u32::from(sub_type & 0xf) << 12,
+            ReparseTag::AppExecLink => 0x8000_001B,
+            ReparseTag::Projfs => 0x9000_001C,
+            ReparseTag::LxSymlink => 0xA000_001D,
+            ReparseTag::StorageSync => 0x8000_001E,
+            ReparseTag::WciTombstone => 0xA000_001F,
+            ReparseTag::Unhandled => 0x8000_0020,
+            ReparseTag::OneDrive => 0x8000_0021,
+            ReparseTag::ProjfsTombstone => 0xA000_0022,
+            ReparseTag::AfUnix => 0x8000_0023,
+            ReparseTag::LxFifo => 0x8000_0024,
+            ReparseTag::LxChr => 0x8000_0025,
+            ReparseTag::LxBlk => 0x8000_0026,
+            ReparseTag::Unknown(tag) => tag,
+        }
+    }
+
+    pub fn is_microsoft(self) -> bool {
+        self.value() & MICROSOFT_BIT != 0
+    }
+
+    /// Returns true if the reparse point redirects to another file or directory,
+    /// in which case the substitute name holds the target.
+    pub fn is_name_surrogate(self) -> bool {
+        self.value() & NAME_SURROGATE_BIT != 0
+    }
+
+    pub fn is_directory(self) -> bool {
+        self.value() & DIRECTORY_BIT != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_tags() {
+        assert_eq!(ReparseTag::from(0xA000_000C), ReparseTag::Symlink);
+        assert_eq!(ReparseTag::from(0xA000_0003), ReparseTag::MountPoint);
+        assert_eq!(ReparseTag::from(0x8000_0017), ReparseTag::Wof);
+
+        assert!(ReparseTag::Symlink.is_name_surrogate());
+        assert!(ReparseTag::MountPoint.is_microsoft());
+        assert!(!ReparseTag::Wof.is_name_surrogate());
+    }
+
+    #[test]
+    fn test_cloud_tags() {
+        assert_eq!(ReparseTag::from(0x9000_001A), ReparseTag::Cloud(0));
+        assert_eq!(ReparseTag::from(0x9000_601A), ReparseTag::Cloud(6));
+        assert_eq!(ReparseTag::Cloud(6).value(), 0x9000_601A);
+        assert!(ReparseTag::Cloud(6).is_directory());
+    }
+
+    #[test]
+    fn test_unknown_tag_roundtrips() {
+        let tag = ReparseTag::from(0x0000_1234);
+
+        assert_eq!(tag, ReparseTag::Unknown(0x0000_1234));
+        assert_eq!(tag.value(), 0x0000_1234);
+        assert!(!tag.is_microsoft());
+    }
+}
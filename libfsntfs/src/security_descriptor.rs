@@ -0,0 +1,403 @@
+use crate::error::Error;
+use crate::guid::Guid;
+use bitflags::bitflags;
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+bitflags! {
+    /// The control flags of a security descriptor (`SE_*`).
+    pub struct SecurityDescriptorControl: u16 {
+        const OWNER_DEFAULTED = 0x0001;
+        const GROUP_DEFAULTED = 0x0002;
+        const DACL_PRESENT = 0x0004;
+        const DACL_DEFAULTED = 0x0008;
+        const SACL_PRESENT = 0x0010;
+        const SACL_DEFAULTED = 0x0020;
+        const DACL_AUTO_INHERIT_REQ = 0x0100;
+        const SACL_AUTO_INHERIT_REQ = 0x0200;
+        const DACL_AUTO_INHERITED = 0x0400;
+        const SACL_AUTO_INHERITED = 0x0800;
+        const DACL_PROTECTED = 0x1000;
+        const SACL_PROTECTED = 0x2000;
+        const RM_CONTROL_VALID = 0x4000;
+        const SELF_RELATIVE = 0x8000;
+    }
+}
+
+bitflags! {
+    /// The inheritance and audit flags of an ACE.
+    pub struct AceFlags: u8 {
+        const OBJECT_INHERIT = 0x01;
+        const CONTAINER_INHERIT = 0x02;
+        const NO_PROPAGATE_INHERIT = 0x04;
+        const INHERIT_ONLY = 0x08;
+        const INHERITED = 0x10;
+        const SUCCESSFUL_ACCESS = 0x40;
+        const FAILED_ACCESS = 0x80;
+    }
+}
+
+const ACE_OBJECT_TYPE_PRESENT: u32 = 0x1;
+const ACE_INHERITED_OBJECT_TYPE_PRESENT: u32 = 0x2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AceType {
+    AccessAllowed,
+    AccessDenied,
+    SystemAudit,
+    SystemAlarm,
+    AccessAllowedCompound,
+    AccessAllowedObject,
+    AccessDeniedObject,
+    SystemAuditObject,
+    SystemAlarmObject,
+    AccessAllowedCallback,
+    AccessDeniedCallback,
+    AccessAllowedCallbackObject,
+    AccessDeniedCallbackObject,
+    SystemAuditCallback,
+    SystemAlarmCallback,
+    SystemAuditCallbackObject,
+    SystemAlarmCallbackObject,
+    SystemMandatoryLabel,
+    SystemResourceAttribute,
+    SystemScopedPolicyId,
+    Unknown(u8),
+}
+
+impl From<u8> for AceType {
+    fn from(ace_type: u8) -> Self {
+        match ace_type {
+            0x00 => AceType::AccessAllowed,
+            0x01 => AceType::AccessDenied,
+            0x02 => AceType::SystemAudit,
+            0x03 => AceType::SystemAlarm,
+            0x04 => AceType::AccessAllowedCompound,
+            0x05 => AceType::AccessAllowedObject,
+            0x06 => AceType::AccessDeniedObject,
+            0x07 => AceType::SystemAuditObject,
+            0x08 => AceType::SystemAlarmObject,
+            0x09 => AceType::AccessAllowedCallback,
+            0x0A => AceType::AccessDeniedCallback,
+            0x0B => AceType::AccessAllowedCallbackObject,
+            0x0C => AceType::AccessDeniedCallbackObject,
+            0x0D => AceType::SystemAuditCallback,
+            0x0E => AceType::SystemAlarmCallback,
+            0x0F => AceType::SystemAuditCallbackObject,
+            0x10 => AceType::SystemAlarmCallbackObject,
+            0x11 => AceType::SystemMandatoryLabel,
+            0x12 => AceType::SystemResourceAttribute,
+            0x13 => AceType::SystemScopedPolicyId,
+            other => AceType::Unknown(other),
+        }
+    }
+}
+
+impl AceType {
+    /// Object ACEs carry optional object type GUIDs before the SID.
+    pub fn is_object(self) -> bool {
+        matches!(
+            self,
+            AceType::AccessAllowedObject
+                | AceType::AccessDeniedObject
+                | AceType::SystemAuditObject
+                | AceType::SystemAlarmObject
+                | AceType::AccessAllowedCallbackObject
+                | AceType::AccessDeniedCallbackObject
+                | AceType::SystemAuditCallbackObject
+                | AceType::SystemAlarmCallbackObject
+        )
+    }
+
+    /// Returns true if the ACE starts with an access mask followed by a SID.
+    fn has_sid(self) -> bool {
+        !matches!(self, AceType::AccessAllowedCompound | AceType::Unknown(_))
+    }
+}
+
+/// A security identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sid {
+    pub revision: u8,
+    /// The 48-bit identifier authority.
+    pub identifier_authority: u64,
+    pub sub_authorities: Vec<u32>,
+}
+
+impl Display for Sid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "S-{}-", self.revision)?;
+
+        if self.identifier_authority >= 1 << 32 {
+            write!(f, "0x{:012X}", self.identifier_authority)?;
+        } else {
+            write!(f, "{}", self.identifier_authority)?;
+        }
+
+        for sub_authority in &self.sub_authorities {
+            write!(f, "-{}", sub_authority)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ace {
+    pub ace_type: AceType,
+    pub flags: AceFlags,
+    pub access_mask: u32,
+    pub object_type: Option<Guid>,
+    pub inherited_object_type: Option<Guid>,
+    /// `None` for compound and unknown ACE types, whose layout is not decoded.
+    pub sid: Option<Sid>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Acl {
+    pub revision: u8,
+    pub aces: Vec<Ace>,
+}
+
+/// A self-relative security descriptor, as stored in `$Secure:$SDS` or `$SECURITY_DESCRIPTOR`.
+#[derive(Debug, Clone)]
+pub struct SecurityDescriptor {
+    pub revision: u8,
+    pub control: SecurityDescriptorControl,
+    pub owner: Option<Sid>,
+    pub group: Option<Sid>,
+    /// `None` if the descriptor has no DACL, or has a NULL DACL (which grants everyone access).
+    pub dacl: Option<Acl>,
+    pub sacl: Option<Acl>,
+}
+
+impl SecurityDescriptor {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        Self::parse(data).map_err(|e| Error::InvalidSecurityDescriptor(e.to_string()))
+    }
+
+    fn parse(data: &[u8]) -> io::Result<Self> {
+        let mut cursor = Cursor::new(data);
+
+        let revision = cursor.read_u8()?;
+        let _sbz1 = cursor.read_u8()?;
+        let control =
+            SecurityDescriptorControl::from_bits_truncate(cursor.read_u16::<LittleEndian>()?);
+        let owner_offset = cursor.read_u32::<LittleEndian>()?;
+        let group_offset = cursor.read_u32::<LittleEndian>()?;
+        let sacl_offset = cursor.read_u32::<LittleEndian>()?;
+        let dacl_offset = cursor.read_u32::<LittleEndian>()?;
+
+        if !control.contains(SecurityDescriptorControl::SELF_RELATIVE) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "only self-relative security descriptors are supported",
+            ));
+        }
+
+        let owner = read_at(data, owner_offset, read_sid)?;
+        let group = read_at(data, group_offset, read_sid)?;
+
+        let sacl = if control.contains(SecurityDescriptorControl::SACL_PRESENT) {
+            read_at(data, sacl_offset, read_acl)?
+        } else {
+            None
+        };
+
+        let dacl = if control.contains(SecurityDescriptorControl::DACL_PRESENT) {
+            read_at(data, dacl_offset, read_acl)?
+        } else {
+            None
+        };
+
+        Ok(SecurityDescriptor {
+            revision,
+            control,
+            owner,
+            group,
+            dacl,
+            sacl,
+        })
+    }
+}
+
+/// Reads a structure at `offset`, where an offset of 0 means the structure is not present.
+fn read_at<T>(
+    data: &[u8],
+    offset: u32,
+    read: fn(&mut Cursor<&[u8]>) -> io::Result<T>,
+) -> io::Result<Option<T>> {
+    if offset == 0 {
+        return Ok(None);
+    }
+
+    if offset as usize >= data.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "offset {} exceeds the descriptor size {}",
+                offset,
+                data.len()
+            ),
+        ));
+    }
+
+    let mut cursor = Cursor::new(data);
+    cursor.seek(SeekFrom::Start(u64::from(offset)))?;
+
+    read(&mut cursor).map(Some)
+}
+
+fn read_sid(cursor: &mut Cursor<&[u8]>) -> io::Result<Sid> {
+    let revision = cursor.read_u8()?;
+    let number_of_sub_authorities = cursor.read_u8()?;
+    let identifier_authority = cursor.read_u48::<BigEndian>()?;
+
+    let mut sub_authorities = Vec::with_capacity(number_of_sub_authorities as usize);
+    for _ in 0..number_of_sub_authorities {
+        sub_authorities.push(cursor.read_u32::<LittleEndian>()?);
+    }
+
+    Ok(Sid {
+        revision,
+        identifier_authority,
+        sub_authorities,
+    })
+}
+
+fn read_guid(cursor: &mut Cursor<&[u8]>) -> io::Result<Guid> {
+    let mut guid = [0; 16];
+    cursor.read_exact(&mut guid)?;
+
+    Ok(Guid::from_bytes(guid))
+}
+
+fn read_acl(cursor: &mut Cursor<&[u8]>) -> io::Result<Acl> {
+    let acl_start = cursor.position();
+
+    let revision = cursor.read_u8()?;
+    let _sbz1 = cursor.read_u8()?;
+    let acl_size = cursor.read_u16::<LittleEndian>()?;
+    let ace_count = cursor.read_u16::<LittleEndian>()?;
+    let _sbz2 = cursor.read_u16::<LittleEndian>()?;
+
+    let acl_end = acl_start + u64::from(acl_size);
+
+    let mut aces = Vec::with_capacity(ace_count as usize);
+    for _ in 0..ace_count {
+        let ace_start = cursor.position();
+
+        let ace_type = AceType::from(cursor.read_u8()?);
+        let flags = AceFlags::from_bits_truncate(cursor.read_u8()?);
+        let ace_size = cursor.read_u16::<LittleEndian>()?;
+
+        let ace_end = ace_start + u64::from(ace_size);
+
+        if ace_size < 4 || ace_end > acl_end {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "ACE at offset {} of size {} exceeds the ACL",
+                    ace_start, ace_size
+                ),
+            ));
+        }
+
+        let mut ace = Ace {
+            ace_type,
+            flags,
+            access_mask: 0,
+            object_type: None,
+            inherited_object_type: None,
+            sid: None,
+        };
+
+        if ace_type.has_sid() {
+            ace.access_mask = cursor.read_u32::<LittleEndian>()?;
+
+            if ace_type.is_object() {
+                let object_flags = cursor.read_u32::<LittleEndian>()?;
+
+                if object_flags & ACE_OBJECT_TYPE_PRESENT != 0 {
+                    ace.object_type = Some(read_guid(cursor)?);
+                }
+                if object_flags & ACE_INHERITED_OBJECT_TYPE_PRESENT != 0 {
+                    ace.inherited_object_type = Some(read_guid(cursor)?);
+                }
+            }
+
+            ace.sid = Some(read_sid(cursor)?);
+        }
+
+        aces.push(ace);
+
+        // Skip any application data trailing the SID.
+        cursor.seek(SeekFrom::Start(ace_end))?;
+    }
+
+    Ok(Acl { revision, aces })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Owner BUILTIN\Administrators, group SYSTEM and a DACL granting Everyone full control.
+    const DESCRIPTOR: [u8; 76] = [
+        0x01, 0x00, 0x04, 0x80, 0x14, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x30, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x20, 0x00,
+        0x00, 0x00, 0x20, 0x02, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x12,
+        0x00, 0x00, 0x00, 0x02, 0x00, 0x1c, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x13, 0x14, 0x00,
+        0xff, 0x01, 0x1f, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    // An ACCESS_ALLOWED_OBJECT ACE for Authenticated Users with an object type GUID.
+    const OBJECT_ACL: [u8; 48] = [
+        0x02, 0x00, 0x30, 0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x02, 0x28, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x0b,
+        0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_parses_descriptor() {
+        let descriptor = SecurityDescriptor::from_bytes(&DESCRIPTOR).unwrap();
+
+        assert_eq!(descriptor.owner.unwrap().to_string(), "S-1-5-32-544");
+        assert_eq!(descriptor.group.unwrap().to_string(), "S-1-5-18");
+        assert!(descriptor.sacl.is_none());
+
+        let dacl = descriptor.dacl.unwrap();
+        assert_eq!(dacl.aces.len(), 1);
+
+        let ace = &dacl.aces[0];
+        assert_eq!(ace.ace_type, AceType::AccessAllowed);
+        assert_eq!(
+            ace.flags,
+            AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT | AceFlags::INHERITED
+        );
+        assert_eq!(ace.access_mask, 0x001f_01ff);
+        assert_eq!(ace.sid.as_ref().unwrap().to_string(), "S-1-1-0");
+    }
+
+    #[test]
+    fn test_parses_object_ace() {
+        let acl = read_acl(&mut Cursor::new(&OBJECT_ACL[..])).unwrap();
+        let ace = &acl.aces[0];
+
+        assert_eq!(ace.ace_type, AceType::AccessAllowedObject);
+        assert_eq!(
+            ace.object_type.unwrap().to_string(),
+            "03020100-0504-0706-0809-0a0b0c0d0e0f"
+        );
+        assert!(ace.inherited_object_type.is_none());
+        assert_eq!(ace.sid.as_ref().unwrap().to_string(), "S-1-5-11");
+    }
+
+    #[test]
+    fn test_rejects_truncated_descriptor() {
+        assert!(SecurityDescriptor::from_bytes(&DESCRIPTOR[..40]).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! A volume which can be shared between threads.
+//!
+//! libfsntfs keeps per-volume state (caches, the current offset of the file IO handle) which
+//! must not be used from two threads at once, so a `Volume` is neither `Send` nor `Sync`.
+//! `SharedVolume` instead keeps a pool of volumes opened from the same image, and lends each
+//! thread a volume of its own.
+use crate::error::Error;
+use crate::volume::{AccessMode, Volume};
+use std::fs::File;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A volume which is not in use by any thread.
+struct PooledVolume(Volume);
+
+// Besides its libfsntfs volume, which is not tied to the thread that opened it, a `Volume`
+// holds:
+//  - `Arc`s, and `RefCell` caches of `Send` data, which are only borrowed through `&Volume`;
+//  - the file IO handle passed to `Volume::open_at_offset`, which is not `Send` in general,
+//    but which `SharedVolume` only ever creates from a `File`.
+// A pooled volume is only moved between threads while no `VolumeGuard` or `OwnedVolume`,
+// and therefore no `FileEntry` or borrow of its caches, exists.
+unsafe impl Send for PooledVolume {}
+
+/// Opens another volume from the image at the given path.
+type Opener = dyn Fn(&Path) -> Result<Volume, Error> + Send + Sync;
+
+/// A `Send + Sync` handle to a volume image, for reading it from multiple threads.
+///
+/// Every thread calls `get` to borrow a `Volume` of its own, and opens its file entries
+/// from it. Volumes are opened on demand and reused once their guard is dropped, so a pool
+/// of worker threads opens at most one volume per thread.
+pub struct SharedVolume {
+    path: PathBuf,
+    open: Box<Opener>,
+    idle: Mutex<Vec<PooledVolume>>,
+}
+
+impl SharedVolume {
+    /// Opens the volume at `path`, which is opened again for every thread that reads it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        SharedVolume::with_opener(path, |path| Volume::open(path, AccessMode::Read))
+    }
+
+    /// Opens the volume which starts at `offset` inside of the image at `path`,
+    /// see `Volume::open_at_offset`.
+    pub fn open_at_offset(
+        path: impl AsRef<Path>,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Self, Error> {
+        SharedVolume::with_opener(path, move |path| {
+            let file = File::open(path).map_err(Error::Io)?;
+
+            Volume::open_at_offset(file, offset, length)
+        })
+    }
+
+    fn with_opener<F>(path: impl AsRef<Path>, open: F) -> Result<Self, Error>
+    where
+        F: Fn(&Path) -> Result<Volume, Error> + Send + Sync + 'static,
+    {
+        let path = path.as_ref().to_owned();
+        let volume = open(&path)?;
+
+        Ok(SharedVolume {
+            path,
+            open: Box::new(open),
+            idle: Mutex::new(vec![PooledVolume(volume)]),
+        })
+    }
+
+    /// Returns the path the volume was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Borrows a volume for the current thread, opening a new one if all are in use.
+    pub fn get(&self) -> Result<VolumeGuard, Error> {
+        Ok(VolumeGuard {
+            shared: self,
+            volume: Some(self.take_volume()?),
+        })
+    }
+
+    fn take_volume(&self) -> Result<Volume, Error> {
+        let idle = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop();
+
+        match idle {
+            Some(PooledVolume(volume)) => Ok(volume),
+            None => (self.open)(&self.path),
+        }
+    }
+
+    /// Returns `volume` to the pool, unless it was signalled to abort.
+    fn return_volume(&self, volume: Volume) {
+        if !volume.is_aborted() {
+            self.idle
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(PooledVolume(volume));
+        }
+    }
+
+    /// Returns the number of volumes which are not in use.
+    pub fn number_of_idle_volumes(&self) -> usize {
+        self.idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+/// A volume borrowed from a `SharedVolume`, which is returned to it on drop.
+///
+/// Volumes which were signalled to abort are closed instead of being reused.
+pub struct VolumeGuard<'a> {
+    shared: &'a SharedVolume,
+    volume: Option<Volume>,
+}
+
+impl<'a> Deref for VolumeGuard<'a> {
+    type Target = Volume;
+
+    fn deref(&self) -> &Volume {
+        self.volume.as_ref().expect("volume is only taken on drop")
+    }
+}
+
+impl<'a> Drop for VolumeGuard<'a> {
+    fn drop(&mut self) {
+        if let Some(volume) = self.volume.take() {
+            self.shared.return_volume(volume);
+        }
+    }
+}
+
+/// A volume taken from a `SharedVolume` by an owned handle, such as an `OwnedFileEntry`,
+/// which is returned to it on drop.
+///
+/// The volume is boxed, so that it stays at the same address while file entries borrow it.
+pub(crate) struct OwnedVolume {
+    shared: Arc<SharedVolume>,
+    volume: Option<Box<Volume>>,
+}
+
+impl OwnedVolume {
+    pub(crate) fn take(shared: Arc<SharedVolume>) -> Result<Self, Error> {
+        let volume = shared.take_volume()?;
+
+        Ok(OwnedVolume {
+            shared,
+            volume: Some(Box::new(volume)),
+        })
+    }
+
+    pub(crate) fn shared(&self) -> &Arc<SharedVolume> {
+        &self.shared
+    }
+}
+
+impl Deref for OwnedVolume {
+    type Target = Volume;
+
+    fn deref(&self) -> &Volume {
+        self.volume.as_ref().expect("volume is only taken on drop")
+    }
+}
+
+impl Drop for OwnedVolume {
+    fn drop(&mut self) {
+        if let Some(volume) = self.volume.take() {
+            self.shared.return_volume(*volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use std::io::Write;
+    use std::thread;
+    use tempdir::TempDir;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_shared_volume_is_send_and_sync() {
+        assert_send_sync::<SharedVolume>();
+    }
+
+    #[test]
+    fn test_volumes_are_reused() {
+        let shared = SharedVolume::open(sample_volume_path()).unwrap();
+
+        {
+            let first = shared.get().unwrap();
+            let second = shared.get().unwrap();
+
+            assert_eq!(first.get_name().unwrap(), second.get_name().unwrap());
+            assert_eq!(shared.number_of_idle_volumes(), 0);
+        }
+
+        assert_eq!(shared.number_of_idle_volumes(), 2);
+    }
+
+    #[test]
+    fn test_read_from_multiple_threads() {
+        let shared = Arc::new(SharedVolume::open(sample_volume_path()).unwrap());
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+
+                thread::spawn(move || {
+                    let volume = shared.get().unwrap();
+                    let entry = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+                    entry.get_name().unwrap()
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), "$MFT");
+        }
+    }
+
+    #[test]
+    fn test_shared_volume_at_offset() {
+        let tmp_dir = TempDir::new("libfsntfs").unwrap();
+        let path = tmp_dir.path().join("disk.dd");
+        File::create(&path)
+            .unwrap()
+            .write_all(&sample_disk_image())
+            .unwrap();
+
+        let shared = SharedVolume::open_at_offset(&path, SAMPLE_PARTITION_OFFSET, None).unwrap();
+
+        let first = shared.get().unwrap();
+        let second = shared.get().unwrap();
+
+        assert_eq!(first.get_name().unwrap(), "KW-SRCH-1");
+        assert_eq!(second.get_name().unwrap(), "KW-SRCH-1");
+    }
+}
@@ -0,0 +1,79 @@
+//! Case-insensitive name comparison, the way NTFS collates file names.
+use byteorder::{ByteOrder, LittleEndian};
+
+/// MFT entry index of `$UpCase`.
+pub(crate) const UPCASE_INDEX: u64 = 10;
+
+/// The volume's `$UpCase` table, which maps every UTF-16 code unit to its upper case form.
+///
+/// NTFS compares names by upcasing each UTF-16 code unit through this table, which was
+/// written when the volume was formatted. It can therefore differ from the case mapping
+/// of the current Unicode version.
+pub(crate) struct UpcaseTable(Vec<u16>);
+
+impl UpcaseTable {
+    pub(crate) fn from_bytes(data: &[u8]) -> Self {
+        let mut table = vec![0; data.len() / 2];
+        LittleEndian::read_u16_into(&data[..table.len() * 2], &mut table);
+
+        UpcaseTable(table)
+    }
+
+    fn upcase(&self, c: u16) -> u16 {
+        self.0.get(c as usize).cloned().unwrap_or(c)
+    }
+
+    /// Compares two names the way NTFS does for the Win32 and DOS namespaces.
+    pub(crate) fn eq_ignore_case(&self, a: &[u16], b: &[u16]) -> bool {
+        a.len() == b.len()
+            && a.iter()
+                .zip(b)
+                .all(|(&x, &y)| self.upcase(x) == self.upcase(y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ascii_table() -> UpcaseTable {
+        let mut data = Vec::new();
+
+        for c in 0..0x500_u16 {
+            let upper = match c {
+                0x61..=0x7a => c - 0x20,
+                // Greek small letters alpha to omega.
+                0x3b1..=0x3c9 => c - 0x20,
+                _ => c,
+            };
+            data.extend_from_slice(&upper.to_le_bytes());
+        }
+
+        UpcaseTable::from_bytes(&data)
+    }
+
+    fn eq_ignore_case(table: &UpcaseTable, a: &str, b: &str) -> bool {
+        let a: Vec<u16> = a.encode_utf16().collect();
+        let b: Vec<u16> = b.encode_utf16().collect();
+
+        table.eq_ignore_case(&a, &b)
+    }
+
+    #[test]
+    fn test_eq_ignore_case() {
+        let table = ascii_table();
+
+        assert!(eq_ignore_case(&table, "$extend", "$Extend"));
+        assert!(eq_ignore_case(&table, "αβγ.txt", "ΑΒΓ.TXT"));
+        assert!(!eq_ignore_case(&table, "$Extend", "$Extended"));
+        assert!(!eq_ignore_case(&table, "a", "b"));
+    }
+
+    #[test]
+    fn test_characters_outside_the_table_compare_exactly() {
+        let table = ascii_table();
+
+        assert!(eq_ignore_case(&table, "ж", "ж"));
+        assert!(!eq_ignore_case(&table, "ж", "Ж"));
+    }
+}
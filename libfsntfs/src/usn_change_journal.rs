@@ -0,0 +1,450 @@
+//! Wraps the libfsntfs USN change journal (`$Extend\$UsnJrnl:$J`).
+//!
+//! libfsntfs only hands out the raw record data, so the USN_RECORD_V2 and USN_RECORD_V3
+//! structures are parsed here. USN_RECORD_V4 records, which track ranges of modified data
+//! and have no name, are skipped.
+use crate::attribute::FileAttributeFlags;
+use crate::error::Error;
+use crate::ffi_error::LibfsntfsErrorRefMut;
+use crate::file_reference::FileReference;
+use crate::name::NtfsName;
+use crate::utils::datetime_from_filetime;
+use crate::volume::Volume;
+use bitflags::bitflags;
+use byteorder::{LittleEndian, ReadBytesExt};
+use chrono::{DateTime, Utc};
+use libfsntfs_sys::off64_t;
+use libyal_rs_common::ffi::AsTypeRef;
+use std::io::{self, Cursor, Seek, SeekFrom};
+use std::os::raw::c_int;
+use std::ptr;
+
+/// The size of a USN change journal block, a record never spans more than one.
+const USN_RECORD_BUFFER_SIZE: usize = 4096;
+
+/// Major version of the range tracking records, which are not parsed.
+const USN_RECORD_V4_MAJOR_VERSION: u16 = 4;
+
+#[repr(C)]
+pub struct __UsnChangeJournal(isize);
+
+pub type UsnChangeJournalRefMut = *mut __UsnChangeJournal;
+pub type UsnChangeJournalRef = *const __UsnChangeJournal;
+
+#[repr(C)]
+pub struct UsnChangeJournal<'a>(UsnChangeJournalRefMut, &'a Volume);
+
+impl<'a> AsTypeRef for UsnChangeJournal<'a> {
+    type Ref = UsnChangeJournalRef;
+    type RefMut = UsnChangeJournalRefMut;
+
+    #[inline]
+    fn as_type_ref(&self) -> Self::Ref {
+        // https://users.rust-lang.org/t/is-it-ub-to-convert-t-to-mut-t/16238/4
+        self.0 as *const _
+    }
+
+    #[inline]
+    fn as_type_ref_mut(&mut self) -> Self::RefMut {
+        self.0
+    }
+
+    #[inline]
+    fn as_raw(&mut self) -> *mut Self::RefMut {
+        &mut self.0 as *mut _
+    }
+}
+
+impl<'a> UsnChangeJournal<'a> {
+    pub fn wrap_ptr(volume: &'a Volume, ptr: UsnChangeJournalRefMut) -> Self {
+        UsnChangeJournal(ptr, volume)
+    }
+}
+
+impl<'a> Drop for UsnChangeJournal<'a> {
+    fn drop(&mut self) {
+        use log::trace;
+
+        let mut error = ptr::null_mut();
+
+        trace!("Calling `libfsntfs_usn_change_journal_free`");
+
+        unsafe {
+            libfsntfs_usn_change_journal_free(self.as_raw(), &mut error);
+        }
+
+        debug_assert!(
+            error.is_null(),
+            "`libfsntfs_usn_change_journal_free` failed!"
+        );
+    }
+}
+
+extern "C" {
+    pub fn libfsntfs_usn_change_journal_free(
+        usn_change_journal: *mut UsnChangeJournalRefMut,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_usn_change_journal_get_offset(
+        usn_change_journal: UsnChangeJournalRef,
+        offset: *mut off64_t,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+    pub fn libfsntfs_usn_change_journal_read_usn_record(
+        usn_change_journal: UsnChangeJournalRef,
+        usn_record_data: *mut u8,
+        usn_record_data_size: usize,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> isize;
+}
+
+bitflags! {
+    /// The reasons a change was recorded in the journal (`USN_REASON_*`).
+    pub struct UsnReason: u32 {
+        const DATA_OVERWRITE = 0x0000_0001;
+        const DATA_EXTEND = 0x0000_0002;
+        const DATA_TRUNCATION = 0x0000_0004;
+        const NAMED_DATA_OVERWRITE = 0x0000_0010;
+        const NAMED_DATA_EXTEND = 0x0000_0020;
+        const NAMED_DATA_TRUNCATION = 0x0000_0040;
+        const FILE_CREATE = 0x0000_0100;
+        const FILE_DELETE = 0x0000_0200;
+        const EA_CHANGE = 0x0000_0400;
+        const SECURITY_CHANGE = 0x0000_0800;
+        const RENAME_OLD_NAME = 0x0000_1000;
+        const RENAME_NEW_NAME = 0x0000_2000;
+        const INDEXABLE_CHANGE = 0x0000_4000;
+        const BASIC_INFO_CHANGE = 0x0000_8000;
+        const HARD_LINK_CHANGE = 0x0001_0000;
+        const COMPRESSION_CHANGE = 0x0002_0000;
+        const ENCRYPTION_CHANGE = 0x0004_0000;
+        const OBJECT_ID_CHANGE = 0x0008_0000;
+        const REPARSE_POINT_CHANGE = 0x0010_0000;
+        const STREAM_CHANGE = 0x0020_0000;
+        const TRANSACTED_CHANGE = 0x0040_0000;
+        const INTEGRITY_CHANGE = 0x0080_0000;
+        const DESIRED_STORAGE_CLASS_CHANGE = 0x0100_0000;
+        const CLOSE = 0x8000_0000;
+    }
+}
+
+bitflags! {
+    /// Additional information about the source of a change (`USN_SOURCE_*`).
+    pub struct UsnSourceInfo: u32 {
+        const DATA_MANAGEMENT = 0x0000_0001;
+        const AUXILIARY_DATA = 0x0000_0002;
+        const REPLICATION_MANAGEMENT = 0x0000_0004;
+        const CLIENT_REPLICATION_MANAGEMENT = 0x0000_0008;
+    }
+}
+
+/// A parsed USN_RECORD_V2 or USN_RECORD_V3 entry.
+#[derive(Debug, Clone)]
+pub struct UsnRecord {
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// For V3 records only the lower 64 bits of the 128-bit identifier are kept,
+    /// the upper half is always zero on NTFS.
+    pub file_reference: FileReference,
+    pub parent_file_reference: FileReference,
+    pub update_sequence_number: i64,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub reason: UsnReason,
+    pub source_info: UsnSourceInfo,
+    pub security_descriptor_identifier: u32,
+    pub file_attribute_flags: FileAttributeFlags,
+    pub name: NtfsName,
+}
+
+impl UsnRecord {
+    /// Parses a single record, as returned by `UsnChangeJournal::read_usn_record`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        Self::parse(data).map_err(|e| Error::InvalidUsnRecord(e.to_string()))
+    }
+
+    /// Parses a single record, returns `None` for a USN_RECORD_V4 record.
+    fn from_bytes_skipping_v4(data: &[u8]) -> Option<Result<Self, Error>> {
+        if data.len() >= 6 && data[4..6] == USN_RECORD_V4_MAJOR_VERSION.to_le_bytes() {
+            None
+        } else {
+            Some(Self::from_bytes(data))
+        }
+    }
+
+    fn parse(data: &[u8]) -> io::Result<Self> {
+        let mut cursor = Cursor::new(data);
+
+        let record_length = cursor.read_u32::<LittleEndian>()?;
+
+        if record_length as usize > data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "record length {} exceeds the available {} bytes",
+                    record_length,
+                    data.len()
+                ),
+            ));
+        }
+
+        let major_version = cursor.read_u16::<LittleEndian>()?;
+        let minor_version = cursor.read_u16::<LittleEndian>()?;
+
+        if major_version != 2 && major_version != 3 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported major version {}", major_version),
+            ));
+        }
+
+        let read_reference = |cursor: &mut Cursor<&[u8]>| -> io::Result<FileReference> {
+            let reference = cursor.read_u64::<LittleEndian>()?;
+
+            if major_version == 3 {
+                cursor.read_u64::<LittleEndian>()?;
+            }
+
+            Ok(FileReference(reference))
+        };
+
+        let file_reference = read_reference(&mut cursor)?;
+        let parent_file_reference = read_reference(&mut cursor)?;
+
+        let update_sequence_number = cursor.read_i64::<LittleEndian>()?;
+        let timestamp = cursor.read_u64::<LittleEndian>()?;
+        let reason = cursor.read_u32::<LittleEndian>()?;
+        let source_info = cursor.read_u32::<LittleEndian>()?;
+        let security_descriptor_identifier = cursor.read_u32::<LittleEndian>()?;
+        let file_attribute_flags = cursor.read_u32::<LittleEndian>()?;
+        let name_size = cursor.read_u16::<LittleEndian>()? as usize;
+        let name_offset = cursor.read_u16::<LittleEndian>()? as usize;
+
+        if name_offset + name_size > record_length as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "name at offset {} of size {} exceeds the record length {}",
+                    name_offset, name_size, record_length
+                ),
+            ));
+        }
+
+        cursor.seek(SeekFrom::Start(name_offset as u64))?;
+
+        let mut name = Vec::with_capacity(name_size / 2);
+        for _ in 0..name_size / 2 {
+            name.push(cursor.read_u16::<LittleEndian>()?);
+        }
+
+        Ok(UsnRecord {
+            major_version,
+            minor_version,
+            file_reference,
+            parent_file_reference,
+            update_sequence_number,
+            timestamp: if timestamp > 0 {
+                Some(datetime_from_filetime(timestamp))
+            } else {
+                None
+            },
+            reason: UsnReason::from_bits_truncate(reason),
+            source_info: UsnSourceInfo::from_bits_truncate(source_info),
+            security_descriptor_identifier,
+            file_attribute_flags: FileAttributeFlags::from_raw(file_attribute_flags),
+            name: NtfsName::from_utf16(name),
+        })
+    }
+}
+
+pub struct IterUsnRecords<'a: 'b, 'b> {
+    handle: &'b mut UsnChangeJournal<'a>,
+    done: bool,
+}
+
+impl<'a: 'b, 'b> Iterator for IterUsnRecords<'a, 'b> {
+    type Item = Result<UsnRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let data = match self.handle.read_usn_record_data() {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    // The journal offset does not advance past a failed read,
+                    // so stop after the first one.
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            // The record was read, so a record which fails to parse does not stop the iteration.
+            if let Some(record) = UsnRecord::from_bytes_skipping_v4(&data) {
+                return Some(record);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> UsnChangeJournal<'a> {
+    /// Retrieves the current offset within the journal data.
+    pub fn get_offset(&self) -> Result<u64, Error> {
+        let mut offset = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_usn_change_journal_get_offset(self.as_type_ref(), &mut offset, &mut error)
+        } != 1
+        {
+            Err(self.1.ffi_error(error))
+        } else {
+            Ok(offset as u64)
+        }
+    }
+
+    /// Reads the next record, returns `None` once the end of the journal is reached.
+    ///
+    /// Fails with `Error::InvalidUsnRecord` for records which cannot be parsed, including
+    /// USN_RECORD_V4 records, in which case the journal offset has already moved past them.
+    pub fn read_usn_record(&mut self) -> Result<Option<UsnRecord>, Error> {
+        match self.read_usn_record_data()? {
+            Some(data) => Ok(Some(UsnRecord::from_bytes(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the data of the next record, returns `None` once the end of the journal is reached.
+    fn read_usn_record_data(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.1.is_aborted() {
+            return Err(Error::Aborted);
+        }
+
+        let mut data = vec![0; USN_RECORD_BUFFER_SIZE];
+        let mut error = ptr::null_mut();
+
+        let read_count = unsafe {
+            libfsntfs_usn_change_journal_read_usn_record(
+                self.as_type_ref(),
+                data.as_mut_ptr(),
+                data.len(),
+                &mut error,
+            )
+        };
+
+        if read_count <= -1 {
+            Err(self.1.ffi_error(error))
+        } else if read_count == 0 {
+            Ok(None)
+        } else {
+            data.truncate(read_count as usize);
+            Ok(Some(data))
+        }
+    }
+
+    /// Iterates over the records of the journal, skipping USN_RECORD_V4 records.
+    ///
+    /// Records which cannot be parsed are yielded as errors, and the iteration continues
+    /// after them. The iteration stops after the first failed read.
+    pub fn iter_records<'b>(&'b mut self) -> IterUsnRecords<'a, 'b> {
+        IterUsnRecords {
+            handle: self,
+            done: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_v2() -> Vec<u8> {
+        let mut data = vec![
+            0x50, 0x00, 0x00, 0x00, // Record length
+            0x02, 0x00, 0x00, 0x00, // Major and minor version
+            0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, // File reference
+            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, // Parent file reference
+            0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // USN
+            0x00, 0x80, 0x3e, 0xd5, 0xde, 0xb1, 0x9d, 0x01, // Timestamp
+            0x00, 0x01, 0x00, 0x80, // Reason
+            0x00, 0x00, 0x00, 0x00, // Source info
+            0x07, 0x01, 0x00, 0x00, // Security identifier
+            0x20, 0x00, 0x00, 0x00, // File attributes
+            0x0e, 0x00, // Name size
+            0x3c, 0x00, // Name offset
+        ];
+        data.extend(
+            "foo.txt"
+                .encode_utf16()
+                .flat_map(|c| c.to_le_bytes().to_vec()),
+        );
+        // Padding to 8-byte alignment.
+        data.extend(&[0; 6]);
+        data
+    }
+
+    #[test]
+    fn test_parses_v2_record() {
+        let record = UsnRecord::from_bytes(&record_v2()).unwrap();
+
+        assert_eq!(record.major_version, 2);
+        assert_eq!(record.file_reference, FileReference::new(42, 3));
+        assert_eq!(record.parent_file_reference, FileReference::new(5, 5));
+        assert_eq!(record.update_sequence_number, 4096);
+        assert_eq!(record.reason, UsnReason::FILE_CREATE | UsnReason::CLOSE);
+        assert!(record.source_info.is_empty());
+        assert_eq!(record.security_descriptor_identifier, 263);
+        assert_eq!(record.file_attribute_flags, FileAttributeFlags::ARCHIVE);
+        assert_eq!(record.name, "foo.txt");
+        assert_eq!(
+            record.timestamp.unwrap().to_rfc3339(),
+            "1970-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_keeps_unknown_file_attribute_flags() {
+        let mut data = record_v2();
+        // 0x0080_0000 has no named flag.
+        data[54] = 0x80;
+
+        let record = UsnRecord::from_bytes(&data).unwrap();
+
+        assert!(record
+            .file_attribute_flags
+            .contains(FileAttributeFlags::ARCHIVE));
+        assert_eq!(record.file_attribute_flags.bits(), 0x0080_0020);
+    }
+
+    #[test]
+    fn test_rejects_truncated_record() {
+        let data = record_v2();
+
+        assert!(UsnRecord::from_bytes(&data[..40]).is_err());
+    }
+
+    #[test]
+    fn test_skips_v4_record() {
+        let mut data = vec![
+            0x50, 0x00, 0x00, 0x00, // Record length
+            0x04, 0x00, 0x00, 0x00, // Major and minor version
+        ];
+        data.extend(&[0; 72]);
+
+        assert!(UsnRecord::from_bytes_skipping_v4(&data).is_none());
+        assert!(UsnRecord::from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_invalid_record_is_not_skipped() {
+        let data = record_v2();
+
+        assert!(UsnRecord::from_bytes_skipping_v4(&data).unwrap().is_ok());
+        assert!(UsnRecord::from_bytes_skipping_v4(&data[..40])
+            .unwrap()
+            .is_err());
+    }
+}
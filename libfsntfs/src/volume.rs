@@ -1,22 +1,32 @@
 use crate::error::Error;
-use crate::ffi_error::{LibfsntfsError, LibfsntfsErrorRef, LibfsntfsErrorRefMut};
+use crate::ffi_error::{__LibfsntfsError, LibfsntfsError, LibfsntfsErrorRef, LibfsntfsErrorRefMut};
 use crate::file_entry::{FileEntry, FileEntryRef, FileEntryRefMut};
+use crate::file_reference::FileReference;
+use crate::hash::{HashManifest, StreamHashes};
+use crate::upcase::{UpcaseTable, UPCASE_INDEX};
+use crate::usn_change_journal::{UsnChangeJournal, UsnChangeJournalRefMut};
+use crate::walk::Walk;
 use libbfio_rs::handle::{Handle, HandleRef};
 use libfsntfs_sys::{
     libfsntfs_file_entry_t, size32_t, LIBFSNTFS_ACCESS_FLAGS,
     LIBFSNTFS_ACCESS_FLAGS_LIBFSNTFS_ACCESS_FLAG_READ,
-    LIBFSNTFS_ACCESS_FLAGS_LIBFSNTFS_ACCESS_FLAG_WRITE,
 };
 use libyal_rs_common::ffi::AsTypeRef;
 use log::error;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::ffi::{c_void, CStr, CString};
 use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::Range;
 use std::os::raw::c_int;
 use std::path::{Iter, Path, PathBuf};
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 
 #[repr(C)]
 pub struct __Volume(isize);
@@ -25,7 +35,102 @@ pub type VolumeRefMut = *mut __Volume;
 pub type VolumeRef = *const __Volume;
 
 #[repr(C)]
-pub struct Volume(VolumeRefMut);
+pub struct Volume {
+    handle: VolumeRefMut,
+    abort_state: Arc<AbortState>,
+    /// Names and parents of the directories visited while resolving paths, by MFT entry index.
+    directory_cache: RefCell<HashMap<MftEntryIndex, CachedDirectory>>,
+    /// Loaded on first use by `get_upcase_table`.
+    upcase_table: RefCell<Option<Arc<UpcaseTable>>>,
+    /// The handle the volume was opened from, if the volume owns it.
+    /// Fields are dropped after `Drop::drop`, so this outlives the libfsntfs volume.
+    file_io_handle: Option<Handle>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedDirectory {
+    sequence_number: u16,
+    /// Escaped with `NtfsName::escape`.
+    name: String,
+    parent_file_reference: FileReference,
+}
+
+/// MFT entry index of the root directory.
+pub(crate) const ROOT_DIRECTORY_INDEX: MftEntryIndex = 5;
+
+/// Prefix of the paths of entries whose parent directory no longer exists.
+const ORPHAN_FILES_DIRECTORY: &str = "/$OrphanFiles";
+
+/// Signature of a BitLocker encrypted volume, in place of the "NTFS    " OEM identifier.
+const BITLOCKER_SIGNATURE: &[u8; 8] = b"-FVE-FS-";
+
+/// Returns whether the boot sector read from `reader` carries the BitLocker signature.
+fn has_bitlocker_signature(mut reader: impl Read) -> bool {
+    let mut boot_sector = [0; 11];
+
+    reader.read_exact(&mut boot_sector).is_ok() && &boot_sector[3..] == BITLOCKER_SIGNATURE
+}
+
+/// MFT entry index of `$Boot`, which covers (at least) the first 8 KiB of the volume.
+const BOOT_INDEX: MftEntryIndex = 7;
+
+/// Offset of the Volume Shadow Snapshots volume header, relative to the start of the volume.
+const VSS_VOLUME_HEADER_OFFSET: u64 = 0x1e00;
+
+/// Identifier at the start of the VSS volume header, `6b870838-76c1-484e-b7ae-04046e6cc752`.
+const VSS_IDENTIFIER: [u8; 16] = [
+    0x6b, 0x87, 0x08, 0x38, 0x76, 0xc1, 0x48, 0x4e, 0xb7, 0xae, 0x04, 0x04, 0x6e, 0x6c, 0xc7, 0x52,
+];
+
+/// Returns whether the VSS volume header read from `reader` carries the VSS identifier.
+fn has_vss_identifier(mut reader: impl Read) -> bool {
+    let mut identifier = [0; 16];
+
+    reader.read_exact(&mut identifier).is_ok() && identifier == VSS_IDENTIFIER
+}
+
+/// State shared between a `Volume` and its `AbortHandle`s.
+struct AbortState {
+    /// Cleared before the volume is freed, so a handle never signals a dangling volume.
+    volume: Mutex<Option<VolumeRefMut>>,
+    aborted: AtomicBool,
+}
+
+// The volume pointer is only used by `libfsntfs_volume_signal_abort` while the lock is held,
+// and `Volume::drop` clears it under the same lock.
+unsafe impl Send for AbortState {}
+unsafe impl Sync for AbortState {}
+
+/// Signals a `Volume` to abort the current activity, possibly from another thread.
+///
+/// Once signalled, operations on the volume fail with `Error::Aborted`.
+#[derive(Clone)]
+pub struct AbortHandle(Arc<AbortState>);
+
+impl AbortHandle {
+    /// Signals the volume to abort the current activity.
+    /// Does nothing if the volume has already been dropped.
+    pub fn abort(&self) -> Result<(), Error> {
+        let volume = self.0.volume.lock().unwrap_or_else(PoisonError::into_inner);
+
+        self.0.aborted.store(true, Ordering::SeqCst);
+
+        if let Some(volume) = *volume {
+            let mut error = ptr::null_mut();
+
+            if unsafe { libfsntfs_volume_signal_abort(volume as VolumeRef, &mut error) } != 1 {
+                return Err(Error::try_from(error)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the volume was signalled to abort.
+    pub fn is_aborted(&self) -> bool {
+        self.0.aborted.load(Ordering::SeqCst)
+    }
+}
 
 impl AsTypeRef for Volume {
     type Ref = VolumeRef;
@@ -34,26 +139,53 @@ impl AsTypeRef for Volume {
     #[inline]
     fn as_type_ref(&self) -> Self::Ref {
         // https://users.rust-lang.org/t/is-it-ub-to-convert-t-to-mut-t/16238/4
-        self.0 as *const _
+        self.handle as *const _
     }
 
     fn as_type_ref_mut(&mut self) -> Self::RefMut {
-        self.0
+        self.handle
     }
 
     fn as_raw(&mut self) -> *mut Self::RefMut {
-        &mut self.0 as *mut _
+        &mut self.handle as *mut _
     }
 }
 
 impl Volume {
     pub fn wrap_ptr(ptr: VolumeRefMut) -> Volume {
-        Volume(ptr)
+        let abort_state = AbortState {
+            volume: Mutex::new(Some(ptr)),
+            aborted: AtomicBool::new(false),
+        };
+
+        Volume {
+            handle: ptr,
+            abort_state: Arc::new(abort_state),
+            directory_cache: RefCell::new(HashMap::new()),
+            upcase_table: RefCell::new(None),
+            file_io_handle: None,
+        }
+    }
+
+    /// Converts an error returned by libfsntfs for an operation on this volume.
+    /// Reports `Error::Aborted` if the volume was signalled to abort.
+    pub(crate) fn ffi_error(&self, error: *mut __LibfsntfsError) -> Error {
+        if self.is_aborted() {
+            Error::Aborted
+        } else {
+            Error::try_from(error).unwrap_or_else(|e| e)
+        }
     }
 }
 
 impl Drop for Volume {
     fn drop(&mut self) {
+        *self
+            .abort_state
+            .volume
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+
         let mut error = ptr::null_mut();
 
         if unsafe { libfsntfs_volume_close(self.as_type_ref(), &mut error) } != 1 {
@@ -92,14 +224,6 @@ extern "C" {
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
     pub fn libfsntfs_volume_close(volume: VolumeRef, error: *mut LibfsntfsErrorRefMut) -> c_int;
-    pub fn libfsntfs_volume_has_bitlocker_drive_encryption(
-        volume: VolumeRef,
-        error: *mut LibfsntfsErrorRefMut,
-    ) -> c_int;
-    pub fn libfsntfs_volume_has_volume_shadow_snapshots(
-        volume: VolumeRef,
-        error: *mut LibfsntfsErrorRefMut,
-    ) -> c_int;
     pub fn libfsntfs_volume_get_cluster_block_size(
         volume: VolumeRef,
         cluster_block_size: *mut usize,
@@ -184,18 +308,141 @@ extern "C" {
         file_entry: *mut FileEntryRefMut,
         error: *mut LibfsntfsErrorRefMut,
     ) -> c_int;
+    pub fn libfsntfs_volume_get_usn_change_journal(
+        volume: VolumeRef,
+        usn_change_journal: *mut UsnChangeJournalRefMut,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+}
+
+#[cfg(windows)]
+extern "C" {
+    pub fn libfsntfs_volume_open_wide(
+        volume: VolumeRef,
+        filename: *const u16,
+        access_flags: c_int,
+        error: *mut LibfsntfsErrorRefMut,
+    ) -> c_int;
+}
+
+/// Opens `volume` by path, without requiring the path to be valid UTF-8.
+#[cfg(unix)]
+fn open_path(
+    volume: &Volume,
+    path: &Path,
+    access_flags: c_int,
+    error: *mut LibfsntfsErrorRefMut,
+) -> Result<c_int, Error> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_string = CString::new(path.as_os_str().as_bytes()).map_err(Error::StringContainsNul)?;
+
+    Ok(unsafe {
+        libfsntfs_volume_open(volume.as_type_ref(), c_string.as_ptr(), access_flags, error)
+    })
 }
 
+/// Opens `volume` by path, without requiring the path to be valid UTF-16.
+#[cfg(windows)]
+fn open_path(
+    volume: &Volume,
+    path: &Path,
+    access_flags: c_int,
+    error: *mut LibfsntfsErrorRefMut,
+) -> Result<c_int, Error> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+
+    if wide.contains(&0) {
+        return Err(Error::Other("Path contains NUL".to_owned()));
+    }
+
+    wide.push(0);
+
+    Ok(unsafe {
+        libfsntfs_volume_open_wide(volume.as_type_ref(), wide.as_ptr(), access_flags, error)
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn open_path(
+    volume: &Volume,
+    path: &Path,
+    access_flags: c_int,
+    error: *mut LibfsntfsErrorRefMut,
+) -> Result<c_int, Error> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| Error::Other("String is invalid UTF-8".to_owned()))?;
+    let c_string = CString::new(path).map_err(Error::StringContainsNul)?;
+
+    Ok(unsafe {
+        libfsntfs_volume_open(volume.as_type_ref(), c_string.as_ptr(), access_flags, error)
+    })
+}
+
+/// Hashes the default and alternate data streams of `entry` into `manifest`.
+///
+/// Streams which cannot be hashed are recorded as errors, the returned error is for the
+/// entry as a whole.
+fn hash_data_streams(
+    path: &str,
+    entry: &FileEntry,
+    manifest: &mut HashManifest,
+) -> Result<(), Error> {
+    if entry.has_default_data_stream()? {
+        let hashes = entry
+            .iter_extents()
+            .and_then(|extents| extents.collect::<Result<Vec<_>, _>>())
+            .and_then(|extents| {
+                StreamHashes::compute(path.to_owned(), None, entry, entry.get_size()?, &extents)
+            });
+
+        match hashes {
+            Ok(hashes) => manifest.streams.push(hashes),
+            Err(e) => manifest.record_error(Some(path), None, e)?,
+        }
+    }
+
+    for stream in entry.iter_alternate_data_streams()? {
+        let mut stream = stream?;
+        let name = stream.get_ntfs_name()?.escape();
+
+        let hashes = stream
+            .iter_extents()
+            .and_then(|extents| extents.collect::<Result<Vec<_>, _>>())
+            .and_then(|extents| {
+                let size = stream.get_size()?;
+                StreamHashes::compute(
+                    path.to_owned(),
+                    Some(name.clone()),
+                    &mut stream,
+                    size,
+                    &extents,
+                )
+            });
+
+        match hashes {
+            Ok(hashes) => manifest.streams.push(hashes),
+            Err(e) => manifest.record_error(Some(path), Some(&name), e)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// The mode to open a volume with.
+///
+/// Volumes are read-only: libfsntfs has no write support, so `Read` is the only mode.
 pub enum AccessMode {
     Read,
-    Write,
 }
 
 impl AccessMode {
     fn as_flag(&self) -> LIBFSNTFS_ACCESS_FLAGS {
         match self {
             AccessMode::Read => LIBFSNTFS_ACCESS_FLAGS_LIBFSNTFS_ACCESS_FLAG_READ,
-            AccessMode::Write => LIBFSNTFS_ACCESS_FLAGS_LIBFSNTFS_ACCESS_FLAG_WRITE,
         }
     }
 }
@@ -203,6 +450,18 @@ pub type MftEntryIndex = u64;
 
 pub type SerialNumber = u64;
 
+/// Geometry and version information of a volume, see `Volume::info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeInfo {
+    pub cluster_size: usize,
+    pub mft_entry_size: u32,
+    pub index_entry_size: u32,
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub serial_number: SerialNumber,
+    pub name: String,
+}
+
 pub struct IterFileEntries<'a> {
     handle: &'a Volume,
     number_of_file_entries: usize,
@@ -214,6 +473,12 @@ impl<'a> Iterator for IterFileEntries<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.idx < self.number_of_file_entries {
+            if self.handle.is_aborted() {
+                self.idx = self.number_of_file_entries;
+
+                return Some(Err(Error::Aborted));
+            }
+
             let entry = self
                 .handle
                 .get_file_entry_by_mft_idx(self.idx as MftEntryIndex);
@@ -228,11 +493,12 @@ impl<'a> Iterator for IterFileEntries<'a> {
 
 impl<'a> Volume {
     /// Opens a volume by filename.
-    pub fn open(filename: impl AsRef<str>, mode: AccessMode) -> Result<Self, Error> {
+    ///
+    /// Returns `Error::BitLockerEncrypted` if the volume cannot be opened because it is
+    /// BitLocker encrypted.
+    pub fn open(filename: impl AsRef<Path>, mode: AccessMode) -> Result<Self, Error> {
         let mut handle = ptr::null_mut();
 
-        let c_string = CString::new(filename.as_ref()).map_err(Error::StringContainsNul)?;
-
         let mut init_error = ptr::null_mut();
 
         let retcode =
@@ -246,16 +512,19 @@ impl<'a> Volume {
 
         let mut error = ptr::null_mut();
 
-        if unsafe {
-            libfsntfs_volume_open(
-                volume.as_type_ref(),
-                c_string.as_ptr(),
-                mode.as_flag() as c_int,
-                &mut error as _,
-            )
-        } != 1
+        if open_path(
+            &volume,
+            filename.as_ref(),
+            mode.as_flag() as c_int,
+            &mut error as _,
+        )? != 1
         {
-            Err(Error::try_from(error)?)
+            let error = Error::try_from(error)?;
+
+            match File::open(filename.as_ref()) {
+                Ok(ref file) if has_bitlocker_signature(file) => Err(Error::BitLockerEncrypted),
+                _ => Err(error),
+            }
         } else {
             Ok(volume)
         }
@@ -291,6 +560,38 @@ impl<'a> Volume {
         }
     }
 
+    /// Opens the volume which starts at `offset` inside of `stream`,
+    /// such as a partition of a full disk image.
+    ///
+    /// Pass the `length` of the partition, if known, so reads cannot run into the next one.
+    ///
+    /// Returns `Error::BitLockerEncrypted` if the volume cannot be opened because it is
+    /// BitLocker encrypted.
+    pub fn open_at_offset(
+        stream: impl Read + Seek + 'static,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Self, Error> {
+        let mut file_io_handle =
+            Handle::from_read_seek_at_offset(stream, offset, length).map_err(Error::IoHandle)?;
+
+        match Volume::open_file_object(&file_io_handle) {
+            Ok(mut volume) => {
+                volume.file_io_handle = Some(file_io_handle);
+                Ok(volume)
+            }
+            Err(e) => {
+                if file_io_handle.seek(SeekFrom::Start(0)).is_ok()
+                    && has_bitlocker_signature(&mut file_io_handle)
+                {
+                    Err(Error::BitLockerEncrypted)
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+
     pub fn iter_entries(&self) -> Result<IterFileEntries, Error> {
         Ok(IterFileEntries {
             handle: self,
@@ -299,6 +600,30 @@ impl<'a> Volume {
         })
     }
 
+    /// Recursively walks the directory tree below `root`, see `Walk`.
+    pub fn walk(&'a self, root: FileEntry<'a>) -> Walk<'a> {
+        Walk::new(self, root)
+    }
+
+    /// Returns whether the volume has Volume Shadow Snapshots, by looking for the VSS volume
+    /// header.
+    ///
+    /// There is no counterpart for BitLocker: an encrypted volume cannot be opened, and
+    /// `Volume::open` reports `Error::BitLockerEncrypted` for it instead.
+    pub fn has_shadow_snapshots(&self) -> Result<bool, Error> {
+        // libfsntfs does not implement this check, so the header is read through `$Boot`.
+        let mut boot = self.get_file_entry_by_mft_idx(BOOT_INDEX)?;
+
+        if boot.get_size()? < VSS_VOLUME_HEADER_OFFSET + VSS_IDENTIFIER.len() as u64 {
+            return Ok(false);
+        }
+
+        boot.seek(SeekFrom::Start(VSS_VOLUME_HEADER_OFFSET))
+            .map_err(Error::Io)?;
+
+        Ok(has_vss_identifier(&mut boot))
+    }
+
     /// Retrieves the volume serial number.
     pub fn get_serial_number(&self) -> Result<SerialNumber, Error> {
         let mut serial_number = 0_u64;
@@ -308,23 +633,176 @@ impl<'a> Volume {
             libfsntfs_volume_get_serial_number(self.as_type_ref(), &mut serial_number, &mut error)
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.ffi_error(error))
         } else {
             Ok(serial_number)
         }
     }
 
+    /// Computes the MD5, SHA-1 and SHA-256 digests of every data stream reachable from the
+    /// root directory, including alternate data streams, reading each stream once.
+    ///
+    /// File entries with multiple names (hard links) are listed under every path, but only
+    /// hashed once. Streams which cannot be read are recorded in `HashManifest::errors`,
+    /// only `Error::Aborted` ends the walk early.
+    pub fn hash_all(&self) -> Result<HashManifest, Error> {
+        let mut manifest = HashManifest::default();
+        // Streams of the file entries hashed so far, as ranges of `manifest.streams`.
+        let mut hashed: HashMap<FileReference, Range<usize>> = HashMap::new();
+
+        for result in self.walk(self.get_root_directory()?) {
+            let (path, entry) = match result {
+                Ok(next) => next,
+                Err(e) => {
+                    manifest.record_error(None, None, e)?;
+                    continue;
+                }
+            };
+
+            let reference = match entry.get_file_reference() {
+                Ok(reference) => reference,
+                Err(e) => {
+                    manifest.record_error(Some(&path), None, e)?;
+                    continue;
+                }
+            };
+
+            if let Some(range) = hashed.get(&reference).cloned() {
+                let links: Vec<StreamHashes> = manifest.streams[range]
+                    .iter()
+                    .map(|stream| StreamHashes {
+                        path: path.clone(),
+                        ..stream.clone()
+                    })
+                    .collect();
+
+                manifest.streams.extend(links);
+                continue;
+            }
+
+            let start = manifest.streams.len();
+            let number_of_errors = manifest.errors.len();
+
+            if let Err(e) = hash_data_streams(&path, &entry, &mut manifest) {
+                manifest.record_error(Some(&path), None, e)?;
+            }
+
+            // Entries which failed are tried again under their next name, if any.
+            if manifest.errors.len() == number_of_errors {
+                hashed.insert(reference, start..manifest.streams.len());
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Retrieves the cluster block size.
+    pub fn get_cluster_block_size(&self) -> Result<usize, Error> {
+        let mut cluster_block_size = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_volume_get_cluster_block_size(
+                self.as_type_ref(),
+                &mut cluster_block_size,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.ffi_error(error))
+        } else {
+            Ok(cluster_block_size)
+        }
+    }
+
+    /// Retrieves the MFT entry size.
+    pub fn get_mft_entry_size(&self) -> Result<u32, Error> {
+        let mut mft_entry_size = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_volume_get_mft_entry_size(self.as_type_ref(), &mut mft_entry_size, &mut error)
+        } != 1
+        {
+            Err(self.ffi_error(error))
+        } else {
+            Ok(mft_entry_size)
+        }
+    }
+
+    /// Retrieves the index entry size.
+    pub fn get_index_entry_size(&self) -> Result<u32, Error> {
+        let mut index_entry_size = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_volume_get_index_entry_size(
+                self.as_type_ref(),
+                &mut index_entry_size,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.ffi_error(error))
+        } else {
+            Ok(index_entry_size)
+        }
+    }
+
+    /// Retrieves the NTFS version as `(major, minor)`.
+    pub fn get_version(&self) -> Result<(u8, u8), Error> {
+        let mut major_version = 0;
+        let mut minor_version = 0;
+        let mut error = ptr::null_mut();
+
+        if unsafe {
+            libfsntfs_volume_get_version(
+                self.as_type_ref(),
+                &mut major_version,
+                &mut minor_version,
+                &mut error,
+            )
+        } != 1
+        {
+            Err(self.ffi_error(error))
+        } else {
+            Ok((major_version, minor_version))
+        }
+    }
+
+    /// Retrieves the geometry, version, serial number and name of the volume.
+    pub fn info(&self) -> Result<VolumeInfo, Error> {
+        let (major_version, minor_version) = self.get_version()?;
+
+        Ok(VolumeInfo {
+            cluster_size: self.get_cluster_block_size()?,
+            mft_entry_size: self.get_mft_entry_size()?,
+            index_entry_size: self.get_index_entry_size()?,
+            major_version,
+            minor_version,
+            serial_number: self.get_serial_number()?,
+            name: self.get_name()?,
+        })
+    }
+
     /// Retrieves a file entry specified by the path.
-    pub fn get_file_entry_by_path(&self, path: impl AsRef<Path>) -> Result<FileEntry, Error> {
+    ///
+    /// Both `/` and `\\` are accepted as separators.
+    /// Returns `None` if there is no file entry at `path`.
+    pub fn get_file_entry_by_path(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Option<FileEntry>, Error> {
         let mut file_entry = ptr::null_mut();
         let mut error = ptr::null_mut();
 
         let path_as_str = path
             .as_ref()
             .to_str()
-            .ok_or_else(|| Error::Other("String is invalid UTF-8".to_owned()))?;
+            .ok_or_else(|| Error::Other("String is invalid UTF-8".to_owned()))?
+            .replace('/', "\\");
 
-        if unsafe {
+        match unsafe {
             libfsntfs_volume_get_file_entry_by_utf8_path(
                 self.as_type_ref(),
                 path_as_str.as_ptr(),
@@ -332,11 +810,10 @@ impl<'a> Volume {
                 &mut file_entry,
                 &mut error,
             )
-        } != 1
-        {
-            Err(Error::try_from(error)?)
-        } else {
-            Ok(FileEntry::wrap_ptr(self, file_entry))
+        } {
+            1 => Ok(Some(FileEntry::wrap_ptr(self, file_entry))),
+            0 => Ok(None),
+            _ => Err(self.ffi_error(error)),
         }
     }
 
@@ -354,18 +831,34 @@ impl<'a> Volume {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.ffi_error(error))
         } else {
             Ok(FileEntry::wrap_ptr(self, file_entry))
         }
     }
 
+    /// Retrieves the file entry a file reference points to.
+    /// Returns `None` if the MFT entry has since been reused, i.e. its sequence number differs.
+    pub fn get_file_entry_by_reference(
+        &self,
+        file_reference: FileReference,
+    ) -> Result<Option<FileEntry>, Error> {
+        let file_entry = self.get_file_entry_by_mft_idx(file_reference.entry_index())?;
+
+        if file_entry.get_file_reference()?.sequence_number() != file_reference.sequence_number() {
+            Ok(None)
+        } else {
+            Ok(Some(file_entry))
+        }
+    }
+
     /// Retrieves the name.
     pub fn get_name(&self) -> Result<String, Error> {
         get_sized_utf8_string!(
             self,
             libfsntfs_volume_get_utf8_name_size,
-            libfsntfs_volume_get_utf8_name
+            libfsntfs_volume_get_utf8_name,
+            self
         )
     }
 
@@ -383,7 +876,7 @@ impl<'a> Volume {
             libfsntfs_volume_get_root_directory(self.as_type_ref(), &mut file_entry, &mut error)
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.ffi_error(error))
         } else {
             Ok(FileEntry::wrap_ptr(self, file_entry))
         }
@@ -402,20 +895,137 @@ impl<'a> Volume {
             )
         } != 1
         {
-            Err(Error::try_from(error)?)
+            Err(self.ffi_error(error))
         } else {
             Ok(number_of_file_entries as usize)
         }
     }
 
     /// Retrieves the USN change journal.
-    fn get_usn_change_journal(&self) {
-        unimplemented!();
+    /// Returns `None` if the volume has no `$UsnJrnl`.
+    pub fn get_usn_change_journal(&self) -> Result<Option<UsnChangeJournal>, Error> {
+        let mut usn_change_journal = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        match unsafe {
+            libfsntfs_volume_get_usn_change_journal(
+                self.as_type_ref(),
+                &mut usn_change_journal,
+                &mut error,
+            )
+        } {
+            1 => Ok(Some(UsnChangeJournal::wrap_ptr(self, usn_change_journal))),
+            0 => Ok(None),
+            _ => Err(self.ffi_error(error)),
+        }
+    }
+
+    /// Builds the full path of an entry named `name` which resides in the directory
+    /// `parent_file_reference`, walking up to the root directory.
+    ///
+    /// Entries whose parent chain is broken (a directory was deleted and its MFT entry reused,
+    /// or the chain loops) are placed under `/$OrphanFiles`, keeping the part of the path
+    /// which could still be resolved.
+    pub(crate) fn resolve_path(
+        &self,
+        name: String,
+        mut parent_file_reference: FileReference,
+    ) -> Result<String, Error> {
+        let number_of_file_entries = self.get_number_of_file_entries()? as MftEntryIndex;
+
+        let mut components = vec![name];
+        let mut visited = HashSet::new();
+
+        let is_orphan = loop {
+            let parent_idx = parent_file_reference.entry_index();
+            let parent_sequence_number = parent_file_reference.sequence_number();
+
+            if parent_idx == ROOT_DIRECTORY_INDEX {
+                break false;
+            }
+
+            if parent_idx >= number_of_file_entries || !visited.insert(parent_idx) {
+                break true;
+            }
+
+            let directory = self.get_cached_directory(parent_idx)?;
+
+            match directory {
+                Some(ref directory) if directory.sequence_number == parent_sequence_number => {
+                    components.push(directory.name.clone());
+                    parent_file_reference = directory.parent_file_reference;
+                }
+                _ => break true,
+            }
+        };
+
+        components.reverse();
+
+        let root = if is_orphan {
+            ORPHAN_FILES_DIRECTORY
+        } else {
+            ""
+        };
+
+        Ok(format!("{}/{}", root, components.join("/")))
+    }
+
+    /// Returns `None` if the entry has no name, and thus cannot be part of a path.
+    fn get_cached_directory(&self, idx: MftEntryIndex) -> Result<Option<CachedDirectory>, Error> {
+        if let Some(directory) = self.directory_cache.borrow().get(&idx) {
+            return Ok(Some(directory.clone()));
+        }
+
+        let entry = self.get_file_entry_by_mft_idx(idx)?;
+
+        let parent_file_reference = match entry.get_optional_parent_file_reference()? {
+            Some(parent_file_reference) => parent_file_reference,
+            None => return Ok(None),
+        };
+
+        let directory = CachedDirectory {
+            sequence_number: entry.get_file_reference()?.sequence_number(),
+            name: entry.get_ntfs_name()?.escape(),
+            parent_file_reference,
+        };
+
+        self.directory_cache
+            .borrow_mut()
+            .insert(idx, directory.clone());
+
+        Ok(Some(directory))
+    }
+
+    /// Retrieves the `$UpCase` table, which is read once and cached.
+    pub(crate) fn get_upcase_table(&self) -> Result<Arc<UpcaseTable>, Error> {
+        if let Some(ref upcase_table) = *self.upcase_table.borrow() {
+            return Ok(upcase_table.clone());
+        }
+
+        let mut data = Vec::new();
+        self.get_file_entry_by_mft_idx(UPCASE_INDEX)?
+            .read_to_end(&mut data)
+            .map_err(|e| Error::Other(format!("Failed to read $UpCase: {}", e)))?;
+
+        let upcase_table = Arc::new(UpcaseTable::from_bytes(&data));
+        *self.upcase_table.borrow_mut() = Some(upcase_table.clone());
+
+        Ok(upcase_table)
     }
 
     /// Signals the volume to abort the current activity.
-    fn signal_abort(&self) {
-        unimplemented!();
+    pub fn signal_abort(&self) -> Result<(), Error> {
+        self.abort_handle().abort()
+    }
+
+    /// Returns a handle which can signal this volume to abort from another thread.
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle(self.abort_state.clone())
+    }
+
+    /// Returns whether the volume was signalled to abort.
+    pub fn is_aborted(&self) -> bool {
+        self.abort_state.aborted.load(Ordering::SeqCst)
     }
 }
 
@@ -423,7 +1033,9 @@ impl<'a> Volume {
 mod tests {
     use super::*;
     use crate::fixtures::*;
+    use digest::Digest;
     use log::{info, trace};
+    use std::io::Cursor;
     use std::path::PathBuf;
 
     #[test]
@@ -437,6 +1049,22 @@ mod tests {
         assert_eq!(volume_name_from_disk, volume_name_from_io_handle)
     }
 
+    #[test]
+    fn test_opens_volume_at_offset() {
+        let mut image = sample_disk_image();
+
+        let length = image.len() as u64 - SAMPLE_PARTITION_OFFSET;
+        // Followed by another partition.
+        image.extend_from_slice(&[0xff; 512]);
+
+        let volume =
+            Volume::open_at_offset(Cursor::new(image), SAMPLE_PARTITION_OFFSET, Some(length))
+                .unwrap();
+
+        assert_eq!(volume.get_name().unwrap(), "KW-SRCH-1");
+        assert!(volume.get_file_entry_by_path("\\$MFT").unwrap().is_some());
+    }
+
     #[test]
     fn test_opens_volume_works() {
         assert!(sample_volume().is_ok());
@@ -462,6 +1090,238 @@ mod tests {
         assert_eq!(volume_name_result.unwrap(), 13425491701870188067)
     }
 
+    #[test]
+    fn test_has_bitlocker_signature() {
+        let mut boot_sector = [0_u8; 512];
+
+        boot_sector[..11].copy_from_slice(b"\xebR\x90-FVE-FS-");
+        assert!(has_bitlocker_signature(&boot_sector[..]));
+
+        boot_sector[..11].copy_from_slice(b"\xebR\x90NTFS    ");
+        assert!(!has_bitlocker_signature(&boot_sector[..]));
+
+        assert!(!has_bitlocker_signature(&b"\xebR\x90-FVE"[..]));
+    }
+
+    #[test]
+    fn test_has_vss_identifier() {
+        let mut header = [0_u8; 512];
+        assert!(!has_vss_identifier(&header[..]));
+
+        header[..16].copy_from_slice(&VSS_IDENTIFIER);
+        assert!(has_vss_identifier(&header[..]));
+
+        assert!(!has_vss_identifier(&VSS_IDENTIFIER[..8]));
+    }
+
+    #[test]
+    fn test_sample_volume_has_no_shadow_snapshots() {
+        let volume = sample_volume().unwrap();
+
+        assert!(!volume.has_shadow_snapshots().unwrap());
+    }
+
+    #[test]
+    fn test_get_file_entry_by_path_accepts_both_separators() {
+        let volume = sample_volume().unwrap();
+
+        let forward = volume.get_file_entry_by_path("/$Extend/$Quota").unwrap();
+        let backward = volume.get_file_entry_by_path("\\$Extend\\$Quota").unwrap();
+
+        assert_eq!(
+            forward.unwrap().get_file_reference().unwrap(),
+            backward.unwrap().get_file_reference().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_file_entry_by_path_missing() {
+        let volume = sample_volume().unwrap();
+
+        assert!(volume
+            .get_file_entry_by_path("/$Extend/does-not-exist")
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::symlink;
+        use tempdir::TempDir;
+
+        let tmp_dir = TempDir::new("libfsntfs").unwrap();
+        let path = tmp_dir.path().join(OsStr::from_bytes(b"\xff.dd"));
+        let sample = Path::new(&sample_volume_path()).canonicalize().unwrap();
+        symlink(sample, &path).unwrap();
+
+        let volume = Volume::open(&path, AccessMode::Read).unwrap();
+
+        assert_eq!(volume.get_name().unwrap(), "KW-SRCH-1");
+    }
+
+    #[test]
+    fn test_hash_all() {
+        let volume = sample_volume().unwrap();
+        let manifest = volume.hash_all().unwrap();
+
+        let mft = manifest
+            .streams
+            .iter()
+            .find(|stream| stream.path == "/$MFT" && stream.stream_name.is_none())
+            .unwrap();
+        let mft_entry = volume.get_file_entry_by_mft_idx(0).unwrap();
+
+        assert_eq!(mft.size, mft_entry.get_size().unwrap());
+        assert_eq!(
+            &mft.digests.sha256[..],
+            &mft_entry.hash::<sha2::Sha256>().unwrap()[..]
+        );
+
+        // `$Secure` only has alternate data streams.
+        assert!(manifest
+            .streams
+            .iter()
+            .any(|stream| stream.stream_path() == "/$Secure:$SDS"));
+
+        // `$BadClus:$Bad` spans the whole volume, but is only sparse, so it hashes as zeros.
+        let bad = manifest
+            .streams
+            .iter()
+            .find(|stream| stream.stream_path() == "/$BadClus:$Bad")
+            .unwrap();
+        assert_eq!(
+            &bad.digests.sha256[..],
+            &sha2::Sha256::digest(&vec![0; bad.size as usize])[..]
+        );
+
+        assert!(manifest.errors.is_empty());
+    }
+
+    #[test]
+    fn test_info() {
+        let info = sample_volume().unwrap().info().unwrap();
+
+        assert!(info.cluster_size.is_power_of_two());
+        assert_eq!(info.mft_entry_size, 1024);
+        assert!(info.index_entry_size.is_power_of_two());
+        assert_eq!(info.major_version, 3);
+        assert_eq!(info.serial_number, 13425491701870188067);
+        assert_eq!(info.name, "KW-SRCH-1");
+    }
+
+    #[test]
+    fn test_abort_from_another_thread() {
+        let volume = sample_volume().unwrap();
+        let abort_handle = volume.abort_handle();
+
+        std::thread::spawn(move || abort_handle.abort().unwrap())
+            .join()
+            .unwrap();
+
+        let mut entries = volume.iter_entries().unwrap();
+
+        match entries.next() {
+            Some(Err(Error::Aborted)) => {}
+            other => panic!("expected `Error::Aborted`, got {:?}", other),
+        }
+        assert!(entries.next().is_none());
+    }
+
+    #[test]
+    fn test_abort_handle_outlives_volume() {
+        let abort_handle = sample_volume().unwrap().abort_handle();
+
+        assert!(abort_handle.abort().is_ok());
+        assert!(abort_handle.is_aborted());
+    }
+
+    #[test]
+    fn test_full_path() {
+        let volume = sample_volume().unwrap();
+
+        let root = volume.get_root_directory().unwrap();
+        assert_eq!(root.full_path().unwrap().unwrap(), "/");
+
+        let mft = volume.get_file_entry_by_mft_idx(0).unwrap();
+        assert_eq!(mft.full_path().unwrap().unwrap(), "/$MFT");
+
+        // Resolving `$Quota` walks through (and caches) the `$Extend` directory.
+        let quota = volume
+            .get_file_entry_by_path("\\$Extend\\$Quota")
+            .unwrap()
+            .unwrap();
+        assert_eq!(quota.full_path().unwrap().unwrap(), "/$Extend/$Quota");
+        assert_eq!(quota.full_path().unwrap().unwrap(), "/$Extend/$Quota");
+    }
+
+    #[test]
+    fn test_resolve_path_with_reused_parent() {
+        let volume = sample_volume().unwrap();
+        let extend = volume
+            .get_file_entry_by_mft_idx(11)
+            .unwrap()
+            .get_file_reference()
+            .unwrap();
+
+        // The parent was deleted, and its MFT entry reused with the next sequence number.
+        let stale_parent = FileReference::new(
+            extend.entry_index(),
+            extend.sequence_number().wrapping_sub(1),
+        );
+
+        assert_eq!(
+            volume.resolve_path("x".to_owned(), stale_parent).unwrap(),
+            "/$OrphanFiles/x"
+        );
+        assert_eq!(
+            volume.resolve_path("x".to_owned(), extend).unwrap(),
+            "/$Extend/x"
+        );
+    }
+
+    #[test]
+    fn test_resolve_path_with_parent_loop() {
+        let volume = sample_volume().unwrap();
+
+        // Two directories which are each other's parent.
+        for &(idx, name, parent_idx) in &[(1, "a", 2), (2, "b", 1)] {
+            volume.directory_cache.borrow_mut().insert(
+                idx,
+                CachedDirectory {
+                    sequence_number: 1,
+                    name: name.to_owned(),
+                    parent_file_reference: FileReference::new(parent_idx, 1),
+                },
+            );
+        }
+
+        assert_eq!(
+            volume
+                .resolve_path("x".to_owned(), FileReference::new(1, 1))
+                .unwrap(),
+            "/$OrphanFiles/b/a/x"
+        );
+    }
+
+    #[test]
+    fn test_get_file_entry_by_reference() {
+        let volume = sample_volume().unwrap();
+        let reference = volume
+            .get_file_entry_by_mft_idx(0)
+            .unwrap()
+            .get_file_reference()
+            .unwrap();
+
+        let mft = volume.get_file_entry_by_reference(reference).unwrap();
+        assert_eq!(mft.unwrap().get_file_reference().unwrap(), reference);
+
+        let stale = FileReference::new(0, reference.sequence_number().wrapping_add(1));
+        assert!(volume.get_file_entry_by_reference(stale).unwrap().is_none());
+    }
+
     #[test]
     fn test_iter_entries() {
         let volume = sample_volume().unwrap();
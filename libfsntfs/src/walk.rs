@@ -0,0 +1,325 @@
+//! Recursive traversal of a directory tree, in the spirit of `walkdir`.
+use crate::error::Error;
+use crate::file_entry::FileEntry;
+use crate::file_reference::FileReference;
+use crate::volume::Volume;
+use std::collections::{HashSet, VecDeque};
+use std::os::raw::c_int;
+
+type Predicate<'a> = Box<dyn FnMut(&str, &FileEntry<'a>) -> bool + 'a>;
+
+/// A directory whose sub entries are yet to be (fully) visited.
+///
+/// The directory is only opened once it becomes the current frame, so a breadth-first walk
+/// does not keep an open file entry for every queued directory.
+struct Frame<'a> {
+    reference: FileReference,
+    path: String,
+    depth: usize,
+    entry: Option<FileEntry<'a>>,
+    number_of_sub_entries: c_int,
+    next_idx: c_int,
+}
+
+/// An iterator over a directory tree, yielding `(path, entry)` pairs.
+///
+/// The root itself is yielded first, at depth 0. Paths are separated by `/`, and names are
+/// escaped with `NtfsName::escape`, so distinct entries never share a path.
+/// Created by `Volume::walk`.
+pub struct Walk<'a> {
+    volume: &'a Volume,
+    root: Option<FileEntry<'a>>,
+    max_depth: Option<usize>,
+    breadth_first: bool,
+    predicate: Option<Predicate<'a>>,
+    frames: VecDeque<Frame<'a>>,
+    /// MFT entry indices of the directories already descended into, a corrupt index may
+    /// otherwise make the walk loop forever.
+    visited: HashSet<u64>,
+    done: bool,
+}
+
+impl<'a> Walk<'a> {
+    pub(crate) fn new(volume: &'a Volume, root: FileEntry<'a>) -> Self {
+        Walk {
+            volume,
+            root: Some(root),
+            max_depth: None,
+            breadth_first: false,
+            predicate: None,
+            frames: VecDeque::new(),
+            visited: HashSet::new(),
+            done: false,
+        }
+    }
+
+    /// Do not yield entries deeper than `depth`, where the root is at depth 0.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Visit every entry of a level before descending into the next one.
+    /// The default is depth-first (pre-order).
+    pub fn breadth_first(mut self, breadth_first: bool) -> Self {
+        self.breadth_first = breadth_first;
+        self
+    }
+
+    /// Only yield entries for which `predicate` returns true.
+    /// Directories which are skipped are not descended into.
+    pub fn filter_entry<P>(mut self, predicate: P) -> Self
+    where
+        P: FnMut(&str, &FileEntry<'a>) -> bool + 'a,
+    {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+
+    fn is_included(&mut self, path: &str, entry: &FileEntry<'a>) -> bool {
+        match self.predicate {
+            Some(ref mut predicate) => predicate(path, entry),
+            None => true,
+        }
+    }
+
+    /// Queues `entry` to be descended into, if it is a directory within the depth limit
+    /// which has not been visited before.
+    fn push_directory(
+        &mut self,
+        path: &str,
+        entry: &FileEntry<'a>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        if self.max_depth.is_some_and(|max_depth| depth >= max_depth)
+            || !entry.has_directory_entries_index()?
+        {
+            return Ok(());
+        }
+
+        let reference = entry.get_file_reference()?;
+
+        if self.visited.insert(reference.entry_index()) {
+            self.frames.push_back(Frame {
+                reference,
+                path: path.to_owned(),
+                depth,
+                entry: None,
+                number_of_sub_entries: 0,
+                next_idx: 0,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn next_root(&mut self, root: FileEntry<'a>) -> Option<Result<(String, FileEntry<'a>), Error>> {
+        let path = match root.full_path() {
+            Ok(Some(path)) => path,
+            Ok(None) => "/".to_owned(),
+            Err(e) => return Some(Err(e)),
+        };
+
+        if !self.is_included(&path, &root) {
+            return None;
+        }
+
+        if let Err(e) = self.push_directory(&path, &root, 0) {
+            return Some(Err(e));
+        }
+
+        Some(Ok((path, root)))
+    }
+
+    fn current_frame(&mut self) -> Option<&mut Frame<'a>> {
+        if self.breadth_first {
+            self.frames.front_mut()
+        } else {
+            self.frames.back_mut()
+        }
+    }
+
+    fn pop_current_frame(&mut self) {
+        if self.breadth_first {
+            self.frames.pop_front();
+        } else {
+            self.frames.pop_back();
+        }
+    }
+
+    /// Advances the current frame, returning `None` once it is exhausted.
+    fn next_in_frame(&mut self) -> Option<Result<(String, FileEntry<'a>, usize), Error>> {
+        let volume = self.volume;
+        let frame = self.current_frame()?;
+
+        if frame.entry.is_none() {
+            let opened = volume
+                .get_file_entry_by_mft_idx(frame.reference.entry_index())
+                .and_then(|entry| {
+                    let number_of_sub_entries = entry.get_number_of_sub_file_entries()?;
+                    Ok((entry, number_of_sub_entries))
+                });
+
+            match opened {
+                Ok((entry, number_of_sub_entries)) => {
+                    frame.entry = Some(entry);
+                    frame.number_of_sub_entries = number_of_sub_entries;
+                }
+                Err(e) => {
+                    // Retrying would fail the same way, so skip the directory.
+                    self.pop_current_frame();
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        if frame.next_idx >= frame.number_of_sub_entries {
+            return None;
+        }
+
+        let idx = frame.next_idx;
+        frame.next_idx += 1;
+
+        let depth = frame.depth + 1;
+        let parent_path = frame.path.trim_end_matches('/').to_owned();
+
+        let sub_entry = frame
+            .entry
+            .as_ref()
+            .expect("frame was opened above")
+            .get_sub_file_entry(idx)
+            .and_then(|sub_entry| {
+                let name = sub_entry.get_ntfs_name()?.escape();
+                Ok((format!("{}/{}", parent_path, name), sub_entry, depth))
+            });
+
+        Some(sub_entry)
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<(String, FileEntry<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if self.volume.is_aborted() {
+            self.done = true;
+            return Some(Err(Error::Aborted));
+        }
+
+        if let Some(root) = self.root.take() {
+            if let Some(result) = self.next_root(root) {
+                return Some(result);
+            }
+        }
+
+        while !self.frames.is_empty() {
+            let (path, entry, depth) = match self.next_in_frame() {
+                Some(Ok(next)) => next,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.pop_current_frame();
+                    continue;
+                }
+            };
+
+            if !self.is_included(&path, &entry) {
+                continue;
+            }
+
+            if let Err(e) = self.push_directory(&path, &entry, depth) {
+                return Some(Err(e));
+            }
+
+            return Some(Ok((path, entry)));
+        }
+
+        self.done = true;
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_walk_depth_first() {
+        let volume = sample_volume().unwrap();
+        let root = volume.get_root_directory().unwrap();
+
+        let paths: Vec<String> = volume.walk(root).map(|r| r.unwrap().0).collect();
+
+        assert_eq!(paths[0], "/");
+        assert!(paths.contains(&"/$MFT".to_owned()));
+        assert!(paths.contains(&"/$Extend/$Quota".to_owned()));
+
+        // A subtree is yielded right after its directory.
+        let extend = paths.iter().position(|p| p == "/$Extend").unwrap();
+        assert!(paths[extend + 1].starts_with("/$Extend/"));
+    }
+
+    #[test]
+    fn test_walk_breadth_first_with_max_depth() {
+        let volume = sample_volume().unwrap();
+        let root = volume.get_root_directory().unwrap();
+
+        let depths: Vec<usize> = volume
+            .walk(root)
+            .breadth_first(true)
+            .max_depth(1)
+            .map(|r| r.unwrap().0)
+            .map(|path| {
+                if path == "/" {
+                    0
+                } else {
+                    path.matches('/').count()
+                }
+            })
+            .collect();
+
+        assert!(depths.len() > 1);
+        assert!(depths.windows(2).all(|w| w[0] <= w[1]));
+        assert!(depths.iter().all(|&d| d <= 1));
+    }
+
+    #[test]
+    fn test_walk_skips_filtered_subtrees() {
+        let volume = sample_volume().unwrap();
+        let root = volume.get_root_directory().unwrap();
+
+        let paths: Vec<String> = volume
+            .walk(root)
+            .filter_entry(|path, _| path != "/$Extend")
+            .map(|r| r.unwrap().0)
+            .collect();
+
+        assert!(paths.contains(&"/$MFT".to_owned()));
+        assert!(!paths.iter().any(|p| p.starts_with("/$Extend")));
+    }
+
+    #[test]
+    fn test_walk_skips_directory_which_fails_to_open() {
+        let volume = sample_volume().unwrap();
+        let root = volume.get_root_directory().unwrap();
+
+        let mut walk = volume.walk(root);
+        walk.root = None;
+        walk.frames.push_back(Frame {
+            reference: FileReference::new(0xffff_ffff, 1),
+            path: "/corrupt".to_owned(),
+            depth: 0,
+            entry: None,
+            number_of_sub_entries: 0,
+            next_idx: 0,
+        });
+
+        assert!(walk.next().unwrap().is_err());
+        assert!(walk.next().is_none());
+    }
+}